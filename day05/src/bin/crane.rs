@@ -0,0 +1,27 @@
+use day05::{process_with_move_type, MoveType};
+use std::{env, fs::read_to_string};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (move_type, path) = match args.as_slice() {
+        [move_type] => (move_type.as_str(), "./input/input.txt"),
+        [move_type, path] => (move_type.as_str(), path.as_str()),
+        _ => {
+            println!("Usage: crane <one-by-one|all-at-once|batched:K|rotating:K|adjacent> [input]");
+            return;
+        }
+    };
+
+    let result = move_type
+        .parse::<MoveType>()
+        .and_then(|move_type| process_with_move_type(&read_to_string(path).unwrap(), move_type));
+    match result {
+        Ok(outcome) => {
+            println!("{}", outcome.top_crates);
+            if let Some(cost) = outcome.cost {
+                println!("cost: {}", cost);
+            }
+        }
+        Err(err) => println!("Invalid input: {}", err),
+    }
+}
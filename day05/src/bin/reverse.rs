@@ -0,0 +1,25 @@
+use day05::{infer_instructions, Error, MoveType};
+use std::{env, fs::read_to_string};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (move_type, start, target) = match args.as_slice() {
+        [move_type, start, target] => (move_type, start, target),
+        _ => {
+            println!("Usage: reverse <move type> <start piles file> <target piles file>");
+            return;
+        }
+    };
+
+    let start = read_to_string(start).unwrap();
+    let target = read_to_string(target).unwrap();
+    let result = move_type
+        .parse::<MoveType>()
+        .and_then(|move_type| infer_instructions(&start, &target, move_type, 1_000_000));
+    match result {
+        Ok(Some(instructions)) => println!("{}", instructions),
+        Ok(None) => println!("The target cannot be reached"),
+        Err(err @ Error::SearchExhausted { .. }) => println!("No instructions found: {}", err),
+        Err(err) => println!("Invalid input: {}", err),
+    }
+}
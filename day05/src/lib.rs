@@ -1,104 +1,426 @@
-pub fn process_part1(input: &str) -> String {
-    let (mut piles, instructions) = parse_input(input);
-    piles.perform_instructions(&instructions, MoveType::OneByOne);
-    piles.top_crates()
+mod solver;
+
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+pub fn process_part1(input: &str) -> Result<String, Error> {
+    process_with_move_type(input, MoveType::OneByOne).map(|outcome| outcome.top_crates)
+}
+
+pub fn process_part2(input: &str) -> Result<String, Error> {
+    process_with_move_type(input, MoveType::AllAtOnce).map(|outcome| outcome.top_crates)
+}
+
+pub fn process_with_move_type(input: &str, move_type: MoveType) -> Result<Outcome, Error> {
+    process_with_crane(input, move_type.crane().as_mut())
+}
+
+pub fn process_with_crane(input: &str, crane: &mut dyn Crane) -> Result<Outcome, Error> {
+    let (mut piles, instructions) = parse_input(input)?;
+    piles.perform_instructions(&instructions, crane)?;
+    Ok(Outcome {
+        top_crates: render_top_crates(&piles.top_crates()),
+        cost: crane.cost(),
+    })
+}
+
+pub fn infer_instructions(
+    start: &str,
+    target: &str,
+    move_type: MoveType,
+    max_states: usize,
+) -> Result<Option<String>, Error> {
+    let start = parse_piles(start.trim_end_matches('\n'))?;
+    let target = parse_piles(target.trim_end_matches('\n'))?;
+    if start.0.len() != target.0.len() {
+        return Err(Error::MalformedPiles(format!(
+            "target has {} stacks but start has {}",
+            target.0.len(),
+            start.0.len()
+        )));
+    }
+
+    let instructions = solver::find_instructions(&start, &target, move_type, max_states)?;
+    Ok(instructions.map(|instructions| {
+        instructions
+            .iter()
+            .map(|instr| instr.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }))
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (mut piles, instructions) = parse_input(input);
-    piles.perform_instructions(&instructions, MoveType::AllAtOnce);
-    piles.top_crates()
+pub struct Outcome {
+    pub top_crates: String,
+    pub cost: Option<usize>,
 }
 
 #[derive(PartialEq, Debug)]
-struct Instruction {
-    count: usize,
-    source: usize,
-    dest: usize,
+pub enum Error {
+    UnknownMoveType(String),
+    MissingInstructions,
+    MalformedPiles(String),
+    MalformedInstruction {
+        index: usize,
+        line: String,
+    },
+    InvalidStack {
+        index: usize,
+        stack_number: usize,
+        num_of_stacks: usize,
+    },
+    NotEnoughCrates {
+        index: usize,
+        stack: usize,
+        available: usize,
+        requested: usize,
+    },
+    SearchExhausted {
+        max_states: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnknownMoveType(name) => write!(
+                f,
+                "unknown move type \"{}\", expected one-by-one, all-at-once, batched:K, rotating:K or adjacent",
+                name
+            ),
+            Error::MissingInstructions => write!(
+                f,
+                "expected a blank line between the piles and the instructions"
+            ),
+            Error::MalformedPiles(reason) => write!(f, "malformed piles: {}", reason),
+            Error::MalformedInstruction { index, line } => {
+                write!(f, "instruction {}: cannot parse \"{}\"", index + 1, line)
+            }
+            Error::InvalidStack {
+                index,
+                stack_number,
+                num_of_stacks,
+            } => write!(
+                f,
+                "instruction {}: stack {} does not exist (there are {} stacks)",
+                index + 1,
+                stack_number,
+                num_of_stacks
+            ),
+            Error::NotEnoughCrates {
+                index,
+                stack,
+                available,
+                requested,
+            } => write!(
+                f,
+                "instruction {}: cannot move {} crates from stack {} which holds {}",
+                index + 1,
+                requested,
+                stack + 1,
+                available
+            ),
+            Error::SearchExhausted { max_states } => write!(
+                f,
+                "gave up after exploring {} pile arrangements",
+                max_states
+            ),
+        }
+    }
+}
+
+// source and dest are zero-based stack indices
+#[derive(PartialEq, Debug, Clone)]
+pub struct Instruction {
+    pub count: usize,
+    pub source: usize,
+    pub dest: usize,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.source + 1,
+            self.dest + 1
+        )
+    }
 }
 
-enum MoveType {
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum MoveType {
     OneByOne,
     AllAtOnce,
+    Batched(usize),
+    Rotating(usize),
+    AdjacentOnly,
+}
+
+impl MoveType {
+    fn crane(&self) -> Box<dyn Crane> {
+        match *self {
+            MoveType::OneByOne => Box::new(OneByOne),
+            MoveType::AllAtOnce => Box::new(AllAtOnce),
+            MoveType::Batched(capacity) => Box::new(Batched(capacity)),
+            MoveType::Rotating(steps) => Box::new(Rotating(steps)),
+            MoveType::AdjacentOnly => Box::new(AdjacentOnly { cost: 0 }),
+        }
+    }
 }
 
-struct Piles(Vec<Vec<char>>);
+impl FromStr for MoveType {
+    type Err = Error;
 
-impl Piles {
-    fn perform_instructions(&mut self, instructions: &[Instruction], move_type: MoveType) {
-        for instr in instructions {
-            match move_type {
-                MoveType::OneByOne => self.move_one_by_one(instr),
-                MoveType::AllAtOnce => self.move_all_at_once(instr),
-            }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || Error::UnknownMoveType(s.to_string());
+        let (name, arg) = match s.split_once(':') {
+            Some((name, arg)) => (name, Some(arg.parse::<usize>().map_err(|_| unknown())?)),
+            None => (s, None),
+        };
+        match (name, arg) {
+            ("one-by-one", None) => Ok(MoveType::OneByOne),
+            ("all-at-once", None) => Ok(MoveType::AllAtOnce),
+            ("batched", Some(capacity)) if capacity > 0 => Ok(MoveType::Batched(capacity)),
+            ("rotating", Some(steps)) => Ok(MoveType::Rotating(steps)),
+            ("adjacent", None) => Ok(MoveType::AdjacentOnly),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+// moving crates from a stack onto itself leaves the piles as they are
+pub trait Crane {
+    fn move_crates(&mut self, piles: &mut Piles, instr: &Instruction);
+
+    fn cost(&self) -> Option<usize> {
+        None
+    }
+}
+
+struct OneByOne;
+
+impl Crane for OneByOne {
+    fn move_crates(&mut self, piles: &mut Piles, instr: &Instruction) {
+        piles.move_group(instr.source, instr.dest, instr.count, true);
+    }
+}
+
+struct AllAtOnce;
+
+impl Crane for AllAtOnce {
+    fn move_crates(&mut self, piles: &mut Piles, instr: &Instruction) {
+        piles.move_group(instr.source, instr.dest, instr.count, false);
+    }
+}
+
+struct Batched(usize);
+
+impl Crane for Batched {
+    fn move_crates(&mut self, piles: &mut Piles, instr: &Instruction) {
+        let mut remaining = instr.count;
+        while remaining > 0 {
+            let count = remaining.min(self.0);
+            piles.move_group(instr.source, instr.dest, count, false);
+            remaining -= count;
+        }
+    }
+}
+
+struct Rotating(usize);
+
+impl Crane for Rotating {
+    fn move_crates(&mut self, piles: &mut Piles, instr: &Instruction) {
+        if instr.source == instr.dest {
+            return;
         }
+        piles.move_group(instr.source, instr.dest, instr.count, false);
+        if instr.count > 0 {
+            let dest = piles.stack_mut(instr.dest);
+            let at = dest.len() - instr.count;
+            dest[at..].rotate_right(self.0 % instr.count);
+        }
+    }
+}
+
+struct AdjacentOnly {
+    cost: usize,
+}
+
+impl Crane for AdjacentOnly {
+    fn move_crates(&mut self, piles: &mut Piles, instr: &Instruction) {
+        let mut at = instr.source;
+        while at != instr.dest {
+            let next = if instr.dest > at { at + 1 } else { at - 1 };
+            piles.move_group(at, next, instr.count, false);
+            self.cost += instr.count;
+            at = next;
+        }
+    }
+
+    fn cost(&self) -> Option<usize> {
+        Some(self.cost)
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct Piles(Vec<Vec<char>>);
+
+impl Piles {
+    pub fn stack(&self, idx: usize) -> &[char] {
+        &self.0[idx]
     }
 
-    fn top_crates(&self) -> String {
-        self.0.iter().map(|stack| stack.last().unwrap()).collect()
+    pub fn stack_mut(&mut self, idx: usize) -> &mut Vec<char> {
+        &mut self.0[idx]
     }
 
-    fn move_one_by_one(&mut self, instr: &Instruction) {
-        for _ in 0..instr.count {
-            let item = self.0[instr.source].pop().unwrap();
-            self.0[instr.dest].push(item);
+    fn perform_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        crane: &mut dyn Crane,
+    ) -> Result<(), Error> {
+        for (index, instr) in instructions.iter().enumerate() {
+            self.check_instruction(index, instr)?;
+            crane.move_crates(self, instr);
         }
+        Ok(())
     }
 
-    fn move_all_at_once(&mut self, instr: &Instruction) {
-        let mut temp: Vec<char> = Vec::new();
-        for _ in 0..instr.count {
-            let item = self.0[instr.source].pop().unwrap();
-            temp.push(item);
+    fn check_instruction(&self, index: usize, instr: &Instruction) -> Result<(), Error> {
+        for stack in [instr.source, instr.dest] {
+            if stack >= self.0.len() {
+                return Err(Error::InvalidStack {
+                    index,
+                    stack_number: stack + 1,
+                    num_of_stacks: self.0.len(),
+                });
+            }
+        }
+
+        let available = self.0[instr.source].len();
+        if available < instr.count {
+            return Err(Error::NotEnoughCrates {
+                index,
+                stack: instr.source,
+                available,
+                requested: instr.count,
+            });
         }
 
-        for item in temp.into_iter().rev() {
-            self.0[instr.dest].push(item);
+        Ok(())
+    }
+
+    fn top_crates(&self) -> Vec<Option<char>> {
+        self.0.iter().map(|stack| stack.last().copied()).collect()
+    }
+
+    pub fn move_group(&mut self, source: usize, dest: usize, count: usize, reverse: bool) {
+        let (source, dest) = match source.cmp(&dest) {
+            Ordering::Less => {
+                let (left, right) = self.0.split_at_mut(dest);
+                (&mut left[source], &mut right[0])
+            }
+            Ordering::Greater => {
+                let (left, right) = self.0.split_at_mut(source);
+                (&mut right[0], &mut left[dest])
+            }
+            Ordering::Equal => return,
+        };
+        let at = source.len() - count;
+        if reverse {
+            dest.extend(source.drain(at..).rev());
+        } else {
+            dest.extend(source.drain(at..));
         }
     }
 }
 
-fn parse_input(input: &str) -> (Piles, Vec<Instruction>) {
-    let (stack_input, instr_input) = input.split_once("\n\n").unwrap();
-    (parse_piles(stack_input), parse_instructions(instr_input))
+fn render_top_crates(top_crates: &[Option<char>]) -> String {
+    top_crates.iter().map(|item| item.unwrap_or(' ')).collect()
+}
+
+fn parse_input(input: &str) -> Result<(Piles, Vec<Instruction>), Error> {
+    let (stack_input, instr_input) = input.split_once("\n\n").ok_or(Error::MissingInstructions)?;
+    let piles = parse_piles(stack_input)?;
+    let instructions = parse_instructions(instr_input, piles.0.len())?;
+    Ok((piles, instructions))
 }
 
-fn parse_piles(input: &str) -> Piles {
-    let (stack_lines, numbers) = input.rsplit_once("\n").unwrap();
-    let num_of_stacks: usize = numbers.split_whitespace().last().unwrap().parse().unwrap();
+fn parse_piles(input: &str) -> Result<Piles, Error> {
+    let (stack_lines, numbers) = input.rsplit_once('\n').unwrap_or(("", input));
+    let num_of_stacks: usize = numbers
+        .split_whitespace()
+        .last()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| Error::MalformedPiles(format!("invalid stack numbers \"{}\"", numbers)))?;
     let mut stacks: Vec<Vec<char>> = vec![Vec::new(); num_of_stacks];
 
     for line in stack_lines.lines().rev() {
         let chars: Vec<char> = line.chars().collect();
-        for (idx, chunk) in chars.chunks(4).into_iter().enumerate() {
-            let maybe_crate = chunk[1];
-            if !maybe_crate.is_whitespace() {
-                stacks[idx].push(maybe_crate);
+        for (idx, chunk) in chars.chunks(4).enumerate() {
+            let maybe_crate = chunk.get(1).copied().unwrap_or(' ');
+            if maybe_crate.is_whitespace() {
+                continue;
+            }
+            match stacks.get_mut(idx) {
+                Some(stack) => stack.push(maybe_crate),
+                None => {
+                    return Err(Error::MalformedPiles(format!(
+                        "crate {} is outside of the {} stacks",
+                        maybe_crate, num_of_stacks
+                    )))
+                }
             }
         }
     }
 
-    return Piles(stacks);
+    Ok(Piles(stacks))
 }
 
-fn parse_instructions(input: &str) -> Vec<Instruction> {
+fn parse_instructions(input: &str, num_of_stacks: usize) -> Result<Vec<Instruction>, Error> {
     input
         .lines()
-        .map(|line| {
-            let nums: Vec<usize> = line
-                .trim()
-                .split_whitespace()
-                .filter_map(|s| s.parse().ok())
-                .collect();
-            Instruction {
-                count: nums[0],
-                source: nums[1] - 1,
-                dest: nums[2] - 1,
-            }
-        })
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(index, line)| parse_instruction(index, line, num_of_stacks))
         .collect()
 }
 
+fn parse_instruction(index: usize, line: &str, num_of_stacks: usize) -> Result<Instruction, Error> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let nums = match tokens.as_slice() {
+        ["move", count, "from", source, "to", dest] => [count, source, dest]
+            .iter()
+            .map(|s| s.parse::<usize>().ok())
+            .collect::<Option<Vec<usize>>>(),
+        _ => None,
+    }
+    .ok_or_else(|| Error::MalformedInstruction {
+        index,
+        line: line.to_string(),
+    })?;
+
+    let to_stack_idx = |stack_number: usize| {
+        if (1..=num_of_stacks).contains(&stack_number) {
+            Ok(stack_number - 1)
+        } else {
+            Err(Error::InvalidStack {
+                index,
+                stack_number,
+                num_of_stacks,
+            })
+        }
+    };
+
+    Ok(Instruction {
+        count: nums[0],
+        source: to_stack_idx(nums[1])?,
+        dest: to_stack_idx(nums[2])?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,8 +451,10 @@ mod tests {
             },
         ];
 
-        piles.perform_instructions(&instructions, MoveType::AllAtOnce);
-        assert_eq!("MCD", piles.top_crates())
+        piles
+            .perform_instructions(&instructions, &mut AllAtOnce)
+            .unwrap();
+        assert_eq!("MCD", render_top_crates(&piles.top_crates()))
     }
 
     #[test]
@@ -159,8 +483,186 @@ mod tests {
             },
         ];
 
-        piles.perform_instructions(&instructions, MoveType::OneByOne);
-        assert_eq!("CMZ", piles.top_crates())
+        piles
+            .perform_instructions(&instructions, &mut OneByOne)
+            .unwrap();
+        assert_eq!("CMZ", render_top_crates(&piles.top_crates()))
+    }
+
+    #[test]
+    fn test_perform_instructions_other_cranes() {
+        let instructions = vec![
+            Instruction {
+                count: 1,
+                source: 1,
+                dest: 0,
+            },
+            Instruction {
+                count: 3,
+                source: 0,
+                dest: 2,
+            },
+            Instruction {
+                count: 2,
+                source: 1,
+                dest: 0,
+            },
+            Instruction {
+                count: 1,
+                source: 0,
+                dest: 1,
+            },
+        ];
+        let cases = vec![
+            (MoveType::Batched(1), "CMZ", None),
+            (MoveType::Batched(2), "MCZ", None),
+            (MoveType::Batched(3), "MCD", None),
+            (MoveType::Rotating(0), "MCD", None),
+            (MoveType::Rotating(1), "CMN", None),
+            (MoveType::AdjacentOnly, "MCD", Some(10)),
+        ];
+
+        for (move_type, expected, expected_cost) in cases {
+            let mut piles = Piles(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+            let mut crane = move_type.crane();
+            piles
+                .perform_instructions(&instructions, crane.as_mut())
+                .unwrap();
+            assert_eq!(expected, render_top_crates(&piles.top_crates()));
+            assert_eq!(expected_cost, crane.cost());
+        }
+    }
+
+    #[test]
+    fn test_parse_move_type() {
+        assert_eq!(Ok(MoveType::OneByOne), "one-by-one".parse());
+        assert_eq!(Ok(MoveType::AllAtOnce), "all-at-once".parse());
+        assert_eq!(Ok(MoveType::Batched(3)), "batched:3".parse());
+        assert_eq!(Ok(MoveType::Rotating(2)), "rotating:2".parse());
+        assert_eq!(Ok(MoveType::AdjacentOnly), "adjacent".parse());
+        assert!("batched:0".parse::<MoveType>().is_err());
+        assert!("batched".parse::<MoveType>().is_err());
+        assert!("crane".parse::<MoveType>().is_err());
+    }
+
+    #[test]
+    fn test_perform_instructions_not_enough_crates() {
+        let mut piles = Piles(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+        let instructions = vec![
+            Instruction {
+                count: 1,
+                source: 2,
+                dest: 0,
+            },
+            Instruction {
+                count: 1,
+                source: 2,
+                dest: 0,
+            },
+        ];
+
+        let result = piles.perform_instructions(&instructions, &mut AllAtOnce);
+        assert_eq!(
+            Err(Error::NotEnoughCrates {
+                index: 1,
+                stack: 2,
+                available: 0,
+                requested: 1
+            }),
+            result
+        );
+        assert_eq!(
+            vec![vec!['Z', 'N', 'P'], vec!['M', 'C', 'D'], vec![]],
+            piles.0
+        );
+    }
+
+    #[test]
+    fn test_top_crates_with_empty_stack() {
+        let piles = Piles(vec![vec!['Z', 'N'], vec![], vec!['P']]);
+        assert_eq!(vec![Some('N'), None, Some('P')], piles.top_crates());
+        assert_eq!("N P", render_top_crates(&piles.top_crates()));
+    }
+
+    #[test]
+    fn test_move_within_same_stack() {
+        let mut piles = Piles(vec![vec!['Z', 'N', 'D']]);
+        let instr = Instruction {
+            count: 2,
+            source: 0,
+            dest: 0,
+        };
+        for move_type in [
+            MoveType::OneByOne,
+            MoveType::AllAtOnce,
+            MoveType::Batched(1),
+            MoveType::Rotating(1),
+            MoveType::AdjacentOnly,
+        ] {
+            move_type.crane().move_crates(&mut piles, &instr);
+            assert_eq!(vec![vec!['Z', 'N', 'D']], piles.0);
+        }
+    }
+
+    #[test]
+    fn test_process_with_crane() {
+        // a crane from outside the crate that only ever moves the top crate
+        struct TopOnly;
+
+        impl Crane for TopOnly {
+            fn move_crates(&mut self, piles: &mut Piles, instr: &Instruction) {
+                piles.move_group(instr.source, instr.dest, 1, false);
+            }
+        }
+
+        let input = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 2 from 2 to 1\nmove 1 from 1 to 3";
+        let outcome = process_with_crane(input, &mut TopOnly).unwrap();
+        assert_eq!("NCD", outcome.top_crates);
+        assert_eq!(None, outcome.cost);
+    }
+
+    #[test]
+    #[ignore]
+    fn stress_perform_instructions() {
+        let num_of_stacks = 9;
+        let crates_per_stack = 1_000_000;
+        let stacks: Vec<Vec<char>> = (0..num_of_stacks)
+            .map(|idx| {
+                (0..crates_per_stack)
+                    .map(|i| char::from(b'A' + ((idx + i) % 26) as u8))
+                    .collect()
+            })
+            .collect();
+
+        // every instruction is immediately undone by the next one, so the
+        // piles must end up exactly where they started
+        let instructions: Vec<Instruction> = (0..2_000_000)
+            .map(|i| {
+                let pair = i / 2;
+                let (a, b) = (pair % num_of_stacks, (pair * 7 + 1) % num_of_stacks);
+                let (source, dest) = if i % 2 == 0 { (a, b) } else { (b, a) };
+                Instruction {
+                    count: pair % 500 + 1,
+                    source,
+                    dest,
+                }
+            })
+            .collect();
+
+        for move_type in [MoveType::OneByOne, MoveType::AllAtOnce] {
+            let mut piles = Piles(stacks.clone());
+            let start = std::time::Instant::now();
+            piles
+                .perform_instructions(&instructions, move_type.crane().as_mut())
+                .unwrap();
+            println!(
+                "{} instructions over {} crates: {:?}",
+                instructions.len(),
+                num_of_stacks * crates_per_stack,
+                start.elapsed()
+            );
+            assert_eq!(stacks, piles.0);
+        }
     }
 
     #[test]
@@ -171,11 +673,17 @@ mod tests {
         input.push_str("[Z] [M] [P]\n");
         input.push_str(" 1   2   3 ");
 
-        let actual = parse_piles(&input);
+        let actual = parse_piles(&input).unwrap();
         let expected = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
         assert_eq!(actual.0, expected);
     }
 
+    #[test]
+    fn test_parse_stacks_too_many_crates() {
+        let input = "[N] [C] [X]\n 1   2 ";
+        assert!(matches!(parse_piles(input), Err(Error::MalformedPiles(_))));
+    }
+
     #[test]
     fn test_parse_instructions() {
         let input = "move 1 from 2 to 1
@@ -183,7 +691,7 @@ mod tests {
             move 2 from 2 to 1
             move 1 from 1 to 2";
 
-        let actual = parse_instructions(input);
+        let actual = parse_instructions(input, 3).unwrap();
         let expected = vec![
             Instruction {
                 count: 1,
@@ -208,4 +716,38 @@ mod tests {
         ];
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_parse_instructions_invalid() {
+        let input = "move 1 from 2 to 1
+            move 3 from 0 to 3";
+        assert_eq!(
+            Err(Error::InvalidStack {
+                index: 1,
+                stack_number: 0,
+                num_of_stacks: 3
+            }),
+            parse_instructions(input, 3)
+        );
+
+        let input = "move 1 from 4 to 1";
+        assert_eq!(
+            Err(Error::InvalidStack {
+                index: 0,
+                stack_number: 4,
+                num_of_stacks: 3
+            }),
+            parse_instructions(input, 3)
+        );
+
+        let input = "move 1 from 2 to 1
+            move 3 from 1";
+        assert_eq!(
+            Err(Error::MalformedInstruction {
+                index: 1,
+                line: String::from("move 3 from 1")
+            }),
+            parse_instructions(input, 3)
+        );
+    }
 }
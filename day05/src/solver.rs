@@ -0,0 +1,187 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{Error, Instruction, MoveType, Piles};
+
+pub(crate) fn find_instructions(
+    start: &Piles,
+    target: &Piles,
+    move_type: MoveType,
+    max_states: usize,
+) -> Result<Option<Vec<Instruction>>, Error> {
+    if start.0.len() != target.0.len() || sorted_crates(start) != sorted_crates(target) {
+        return Ok(None);
+    }
+
+    let mut states: Vec<(Piles, Option<(usize, Instruction)>)> = vec![(start.clone(), None)];
+    let mut best_steps: HashMap<Piles, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut queue = BinaryHeap::from([Reverse((heuristic(start, target), 0, 0))]);
+
+    while let Some(Reverse((_, steps, id))) = queue.pop() {
+        let piles = states[id].0.clone();
+        if piles == *target {
+            return Ok(Some(instructions_to(&states, id)));
+        }
+        if best_steps.get(&piles).is_some_and(|best| *best < steps) {
+            continue;
+        }
+
+        for instr in candidate_instructions(&piles) {
+            let mut next = piles.clone();
+            move_type.crane().move_crates(&mut next, &instr);
+            if best_steps.get(&next).is_some_and(|best| *best <= steps + 1) {
+                continue;
+            }
+            if states.len() >= max_states {
+                return Err(Error::SearchExhausted { max_states });
+            }
+
+            best_steps.insert(next.clone(), steps + 1);
+            queue.push(Reverse((
+                steps + 1 + heuristic(&next, target),
+                steps + 1,
+                states.len(),
+            )));
+            states.push((next, Some((id, instr))));
+        }
+    }
+
+    Ok(None)
+}
+
+fn sorted_crates(piles: &Piles) -> Vec<char> {
+    let mut crates: Vec<char> = piles.0.iter().flatten().copied().collect();
+    crates.sort_unstable();
+    crates
+}
+
+// a single instruction changes at most two stacks (cranes that relay through
+// other stacks leave them as they were), so this never overestimates
+fn heuristic(piles: &Piles, target: &Piles) -> usize {
+    let mismatched = piles
+        .0
+        .iter()
+        .zip(target.0.iter())
+        .filter(|(stack, target_stack)| stack != target_stack)
+        .count();
+    mismatched.div_ceil(2)
+}
+
+fn candidate_instructions(piles: &Piles) -> Vec<Instruction> {
+    let num_of_stacks = piles.0.len();
+    (0..num_of_stacks)
+        .flat_map(|source| {
+            (0..num_of_stacks)
+                .filter(move |dest| *dest != source)
+                .flat_map(move |dest| {
+                    (1..=piles.0[source].len()).map(move |count| Instruction {
+                        count,
+                        source,
+                        dest,
+                    })
+                })
+        })
+        .collect()
+}
+
+fn instructions_to(
+    states: &[(Piles, Option<(usize, Instruction)>)],
+    id: usize,
+) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut current = id;
+    while let Some((parent, instr)) = &states[current].1 {
+        instructions.push(instr.clone());
+        current = *parent;
+    }
+    instructions.reverse();
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_piles() -> Piles {
+        Piles(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']])
+    }
+
+    #[test]
+    fn test_find_instructions() {
+        for move_type in [
+            MoveType::OneByOne,
+            MoveType::AllAtOnce,
+            MoveType::Batched(2),
+            MoveType::Rotating(1),
+            MoveType::AdjacentOnly,
+        ] {
+            let start = example_piles();
+            let target = Piles(vec![vec!['C'], vec!['M'], vec!['P', 'D', 'N', 'Z']]);
+            let instructions = find_instructions(&start, &target, move_type, 1_000_000)
+                .unwrap()
+                .unwrap();
+            assert!(instructions.len() <= 4);
+
+            let mut piles = start.clone();
+            piles
+                .perform_instructions(&instructions, move_type.crane().as_mut())
+                .unwrap();
+            assert_eq!(target, piles);
+        }
+    }
+
+    #[test]
+    fn test_find_instructions_shortest() {
+        let start = example_piles();
+        let target = Piles(vec![vec!['Z', 'N', 'C', 'D'], vec!['M'], vec!['P']]);
+        let instructions = find_instructions(&start, &target, MoveType::AllAtOnce, 1000)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            vec![Instruction {
+                count: 2,
+                source: 1,
+                dest: 0
+            }],
+            instructions
+        );
+        assert_eq!(
+            Ok(Some(vec![])),
+            find_instructions(&start, &start, MoveType::OneByOne, 1000)
+        );
+    }
+
+    #[test]
+    fn test_find_instructions_unreachable() {
+        let start = example_piles();
+        let target = Piles(vec![vec!['Z', 'N'], vec!['M', 'C', 'X'], vec!['P']]);
+        assert_eq!(
+            Ok(None),
+            find_instructions(&start, &target, MoveType::OneByOne, 1000)
+        );
+
+        // with a single stack there is nowhere to move crates to
+        let target = Piles(vec![vec!['N', 'Z']]);
+        assert_eq!(
+            Ok(None),
+            find_instructions(
+                &Piles(vec![vec!['Z', 'N']]),
+                &target,
+                MoveType::OneByOne,
+                1000
+            )
+        );
+    }
+
+    #[test]
+    fn test_find_instructions_exhausted() {
+        let start = example_piles();
+        let target = Piles(vec![vec!['N', 'Z', 'P', 'M', 'C', 'D'], vec![], vec![]]);
+        assert_eq!(
+            Err(Error::SearchExhausted { max_states: 3 }),
+            find_instructions(&start, &target, MoveType::AllAtOnce, 3)
+        );
+    }
+}
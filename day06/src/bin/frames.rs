@@ -0,0 +1,39 @@
+use day06::{decode_frames, find_all_markers, statistics};
+use std::{env, fs::read};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let marker_length: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(4);
+    let path = args.get(1).map_or("./input/input.txt", |s| s.as_str());
+    let input = read(path).unwrap();
+
+    let frames = decode_frames(&input, marker_length);
+    let markers = find_all_markers(&input, marker_length);
+    let stats = statistics(&input, &frames, &markers);
+    for frame in &frames {
+        println!(
+            "frame at {}: payload {}..{} ({} bytes)",
+            frame.start,
+            frame.payload_start,
+            frame.end,
+            frame.payload_len()
+        );
+    }
+    println!("preamble: {} bytes", stats.preamble_length);
+    println!("frames: {}", stats.frame_lengths.len());
+    if let (Some(min), Some(max)) = (
+        stats.frame_lengths.iter().min(),
+        stats.frame_lengths.iter().max(),
+    ) {
+        println!("frame length: min {} max {}", min, max);
+    }
+    println!("markers: {}", markers.len());
+    if let (Some(min), Some(max)) = (
+        stats.marker_gaps.iter().min(),
+        stats.marker_gaps.iter().max(),
+    ) {
+        println!("marker gap: min {} max {}", min, max);
+    }
+    println!("longest distinct window: {}", stats.longest_distinct_window);
+    println!("min distinct window: {}", stats.min_distinct_window);
+}
@@ -1,8 +1,11 @@
 use day06::process_part2;
-use std::fs::read_to_string;
+use std::fs::read;
 
 fn main() {
-  let input = read_to_string("./input/input.txt").unwrap();
-  let output = process_part2(&input);
-  println!("{}", output);  
+    let input = read("./input/input.txt").unwrap();
+    let result = process_part2(&input);
+    match result {
+        Some(output) => println!("{}", output),
+        None => println!("No marker found"),
+    }
 }
@@ -0,0 +1,23 @@
+use day06::detect_markers;
+use std::{
+    env,
+    fs::File,
+    io::{self, Read},
+};
+
+fn main() {
+    let reader: Box<dyn Read> = match env::args().nth(1) {
+        Some(path) => Box::new(File::open(path).unwrap()),
+        None => Box::new(io::stdin()),
+    };
+
+    let detector = detect_markers(reader).unwrap();
+    match detector.start_of_packet() {
+        Some(offset) => println!("start-of-packet: {}", offset),
+        None => println!("No start-of-packet marker found"),
+    }
+    match detector.start_of_message() {
+        Some(offset) => println!("start-of-message: {}", offset),
+        None => println!("No start-of-message marker found"),
+    }
+}
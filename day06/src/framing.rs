@@ -0,0 +1,291 @@
+use std::collections::HashSet;
+
+use crate::Window;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Frame {
+    pub start: usize,
+    pub payload_start: usize,
+    pub end: usize,
+}
+
+impl Frame {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn payload_len(&self) -> usize {
+        self.end - self.payload_start
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Statistics {
+    pub preamble_length: usize,
+    pub frame_lengths: Vec<usize>,
+    pub marker_gaps: Vec<usize>,
+    pub longest_distinct_window: usize,
+    pub min_distinct_window: usize,
+}
+
+pub fn find_all_markers(message: &[u8], marker_length: usize) -> Vec<usize> {
+    if marker_length == 0 {
+        return vec![0];
+    }
+
+    let mut window = Window::new(marker_length);
+    message
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| window.push(**byte))
+        .map(|(idx, _)| idx + 1)
+        .collect()
+}
+
+// a zero-length marker is found before the first byte but cannot introduce
+// frames, since it would introduce one at every byte
+pub fn decode_frames(message: &[u8], marker_length: usize) -> Vec<Frame> {
+    if marker_length == 0 {
+        return Vec::new();
+    }
+
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut window = Window::new(marker_length);
+    for (idx, &byte) in message.iter().enumerate() {
+        if window.push(byte) {
+            let start = idx + 1 - marker_length;
+            if let Some(last) = frames.last_mut() {
+                last.end = start;
+            }
+            frames.push(Frame {
+                start,
+                payload_start: idx + 1,
+                end: message.len(),
+            });
+            window = Window::new(marker_length);
+        }
+    }
+    frames
+}
+
+// takes the frames and markers found in the message, so callers that show
+// them as well do not decode the message twice
+pub fn statistics(message: &[u8], frames: &[Frame], markers: &[usize]) -> Statistics {
+    Statistics {
+        preamble_length: frames.first().map_or(message.len(), |frame| frame.start),
+        frame_lengths: frames.iter().map(|frame| frame.len()).collect(),
+        marker_gaps: markers.windows(2).map(|pair| pair[1] - pair[0]).collect(),
+        longest_distinct_window: longest_distinct_window(message),
+        min_distinct_window: min_distinct_window(message),
+    }
+}
+
+fn longest_distinct_window(message: &[u8]) -> usize {
+    let mut last_seen = [None; 256];
+    let mut window_start = 0;
+    let mut longest = 0;
+    for (idx, &byte) in message.iter().enumerate() {
+        if let Some(seen) = last_seen[byte as usize] {
+            window_start = window_start.max(seen + 1);
+        }
+        last_seen[byte as usize] = Some(idx);
+        longest = longest.max(idx + 1 - window_start);
+    }
+    longest
+}
+
+// length of the shortest window containing every distinct byte of the message
+fn min_distinct_window(message: &[u8]) -> usize {
+    let mut needed = [false; 256];
+    for &byte in message {
+        needed[byte as usize] = true;
+    }
+    let distinct = needed.iter().filter(|needed| **needed).count();
+
+    let mut counts = [0usize; 256];
+    let mut covered = 0;
+    let mut window_start = 0;
+    let mut shortest = message.len();
+    for (idx, &byte) in message.iter().enumerate() {
+        counts[byte as usize] += 1;
+        if counts[byte as usize] == 1 {
+            covered += 1;
+        }
+        while counts[message[window_start] as usize] > 1 {
+            counts[message[window_start] as usize] -= 1;
+            window_start += 1;
+        }
+        if covered == distinct {
+            shortest = shortest.min(idx + 1 - window_start);
+        }
+    }
+    shortest
+}
+
+const SYMBOLS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+// payloads only use marker_length - 1 symbols and end with a repeated byte
+// which the following marker also starts with, so no window of distinct
+// bytes can appear anywhere except at the generated markers; markers need at
+// least two and at most as many symbols as there are
+pub fn generate_stream(
+    num_of_frames: usize,
+    marker_length: usize,
+    max_payload_length: usize,
+    seed: u64,
+) -> Option<(Vec<u8>, Vec<Frame>)> {
+    if !(2..=SYMBOLS.len()).contains(&marker_length) {
+        return None;
+    }
+    let mut rng = Rng(seed);
+    let mut stream = generate_payload(&mut rng, marker_length, max_payload_length);
+    let mut frames = Vec::new();
+
+    for _ in 0..num_of_frames {
+        let start = stream.len();
+        let marker = generate_marker(&mut rng, marker_length, stream.last().copied());
+        stream.extend(marker);
+        let payload_start = stream.len();
+        stream.extend(generate_payload(
+            &mut rng,
+            marker_length,
+            max_payload_length,
+        ));
+        frames.push(Frame {
+            start,
+            payload_start,
+            end: stream.len(),
+        });
+    }
+
+    Some((stream, frames))
+}
+
+fn generate_payload(rng: &mut Rng, marker_length: usize, max_length: usize) -> Vec<u8> {
+    let length = match rng.below(max_length + 1) {
+        1 => 0,
+        length => length,
+    };
+    let mut payload: Vec<u8> = (0..length)
+        .map(|_| SYMBOLS[rng.below(marker_length - 1)])
+        .collect();
+    if length >= 2 {
+        payload[length - 1] = payload[length - 2];
+    }
+    payload
+}
+
+fn generate_marker(rng: &mut Rng, marker_length: usize, previous: Option<u8>) -> Vec<u8> {
+    let mut marker = vec![previous.unwrap_or(SYMBOLS[rng.below(SYMBOLS.len())])];
+    let mut used: HashSet<u8> = marker.iter().copied().collect();
+    while marker.len() < marker_length {
+        let symbol = SYMBOLS[rng.below(SYMBOLS.len())];
+        if used.insert(symbol) {
+            marker.push(symbol);
+        }
+    }
+    marker
+}
+
+pub(crate) struct Rng(pub(crate) u64);
+
+impl Rng {
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % bound.max(1) as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_all_markers() {
+        assert_eq!(vec![5, 6, 7], find_all_markers(b"aabcdef", 4));
+        assert_eq!(Vec::<usize>::new(), find_all_markers(b"abcabc", 4));
+        assert_eq!(vec![0], find_all_markers(b"abc", 0));
+        assert_eq!(
+            crate::find_first_marker(b"", 0),
+            find_all_markers(b"", 0).first().copied()
+        );
+    }
+
+    #[test]
+    fn test_decode_frames() {
+        let frames = decode_frames(b"xxxbcdyyyefgzz", 4);
+        assert_eq!(
+            vec![
+                Frame {
+                    start: 2,
+                    payload_start: 6,
+                    end: 8
+                },
+                Frame {
+                    start: 8,
+                    payload_start: 12,
+                    end: 14
+                },
+            ],
+            frames
+        );
+        assert_eq!(Vec::<Frame>::new(), decode_frames(b"abc", 0));
+    }
+
+    #[test]
+    fn test_statistics() {
+        let message = b"xxxbcdyyyefgzz";
+        let stats = statistics(
+            message,
+            &decode_frames(message, 4),
+            &find_all_markers(message, 4),
+        );
+        assert_eq!(
+            Statistics {
+                preamble_length: 2,
+                frame_lengths: vec![6, 6],
+                marker_gaps: vec![1, 5, 1],
+                longest_distinct_window: 5,
+                min_distinct_window: 11,
+            },
+            stats
+        );
+    }
+
+    #[test]
+    fn test_min_distinct_window() {
+        assert_eq!(0, min_distinct_window(b""));
+        assert_eq!(1, min_distinct_window(b"aaaa"));
+        assert_eq!(3, min_distinct_window(b"aabcbc"));
+        assert_eq!(4, min_distinct_window(b"aabcbcdbca"));
+    }
+
+    #[test]
+    fn test_generate_stream_marker_length() {
+        assert_eq!(None, generate_stream(5, 0, 10, 0));
+        assert_eq!(None, generate_stream(5, 1, 10, 0));
+        assert_eq!(None, generate_stream(5, 63, 10, 0));
+        assert!(generate_stream(5, 62, 10, 0).is_some());
+    }
+
+    #[test]
+    fn test_generate_stream_round_trip() {
+        for marker_length in [2, 4, 14, 62] {
+            for seed in 0..20 {
+                let (stream, frames) = generate_stream(50, marker_length, 40, seed).unwrap();
+                assert_eq!(frames, decode_frames(&stream, marker_length));
+                assert_eq!(
+                    frames.first().map(|frame| frame.payload_start),
+                    crate::find_first_marker(&stream, marker_length)
+                );
+            }
+        }
+    }
+}
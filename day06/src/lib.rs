@@ -1,60 +1,276 @@
-use itertools::Itertools;
+mod framing;
 
-pub fn process_part1(message: &str) -> usize {
+pub use framing::{
+    decode_frames, find_all_markers, generate_stream, statistics, Frame, Statistics,
+};
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+};
+
+pub fn process_part1(message: &[u8]) -> Option<usize> {
     find_first_marker(message, 4)
 }
 
-pub fn process_part2(message: &str) -> usize {
+pub fn process_part2(message: &[u8]) -> Option<usize> {
     find_first_marker(message, 14)
 }
 
-fn find_first_marker(message: &str, marker_length: usize) -> usize {
-    let vec: Vec<char> = message.chars().collect();
-    let slice = &vec[..];
-    match slice.windows(marker_length).position(are_all_unique) {
-        Some(idx) => idx + marker_length,
-        None => message.len(),
+pub fn detect_markers<R: Read>(mut reader: R) -> io::Result<MarkerDetector> {
+    let mut detector = MarkerDetector::default();
+    let mut buffer = [0u8; 8192];
+    while !detector.is_done() {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        detector.feed(&buffer[..read]);
+    }
+    Ok(detector)
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Marker {
+    StartOfPacket(usize),
+    StartOfMessage(usize),
+}
+
+pub struct MarkerDetector {
+    packet: Window,
+    message: Window,
+    offset: usize,
+    start_of_packet: Option<usize>,
+    start_of_message: Option<usize>,
+}
+
+impl Default for MarkerDetector {
+    fn default() -> Self {
+        Self::new(4, 14)
     }
 }
 
-fn are_all_unique(slice: &[char]) -> bool {
-    slice.iter().all_unique()
+impl MarkerDetector {
+    pub fn new(packet_marker_length: usize, message_marker_length: usize) -> Self {
+        // an empty marker is complete before the first byte, as in find_first_marker
+        MarkerDetector {
+            packet: Window::new(packet_marker_length),
+            message: Window::new(message_marker_length),
+            offset: 0,
+            start_of_packet: (packet_marker_length == 0).then_some(0),
+            start_of_message: (message_marker_length == 0).then_some(0),
+        }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Marker> {
+        let mut found = Vec::new();
+        for (idx, &byte) in bytes.iter().enumerate() {
+            if self.is_done() {
+                self.offset += bytes.len() - idx;
+                break;
+            }
+            self.offset += 1;
+            if self.start_of_packet.is_none() && self.packet.push(byte) {
+                self.start_of_packet = Some(self.offset);
+                found.push(Marker::StartOfPacket(self.offset));
+            }
+            if self.start_of_message.is_none() && self.message.push(byte) {
+                self.start_of_message = Some(self.offset);
+                found.push(Marker::StartOfMessage(self.offset));
+            }
+        }
+        found
+    }
+
+    pub fn start_of_packet(&self) -> Option<usize> {
+        self.start_of_packet
+    }
+
+    pub fn start_of_message(&self) -> Option<usize> {
+        self.start_of_message
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.start_of_packet.is_some() && self.start_of_message.is_some()
+    }
+}
+
+struct Window {
+    length: usize,
+    counts: [usize; 256],
+    duplicates: usize,
+    recent: VecDeque<u8>,
+}
+
+impl Window {
+    fn new(length: usize) -> Self {
+        Window {
+            length,
+            counts: [0; 256],
+            duplicates: 0,
+            recent: VecDeque::with_capacity(length + 1),
+        }
+    }
+
+    fn push(&mut self, byte: u8) -> bool {
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicates += 1;
+        }
+        self.recent.push_back(byte);
+
+        if self.recent.len() > self.length {
+            if let Some(old) = self.recent.pop_front() {
+                self.counts[old as usize] -= 1;
+                if self.counts[old as usize] == 1 {
+                    self.duplicates -= 1;
+                }
+            }
+        }
+
+        self.recent.len() == self.length && self.duplicates == 0
+    }
+}
+
+fn find_first_marker(message: &[u8], marker_length: usize) -> Option<usize> {
+    if marker_length == 0 {
+        return Some(0);
+    }
+
+    let mut window = Window::new(marker_length);
+    message
+        .iter()
+        .position(|byte| window.push(*byte))
+        .map(|idx| idx + 1)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::framing::Rng;
+    use std::collections::HashSet;
 
     #[test]
     fn test_find_first_marker() {
-        let message = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-        assert_eq!(7, find_first_marker(message, 4));
+        let message = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        assert_eq!(Some(7), find_first_marker(message, 4));
+
+        let message = b"bvwbjplbgvbhsrlpgdmjqwftvncz";
+        assert_eq!(Some(5), find_first_marker(message, 4));
+
+        let message = b"nppdvjthqldpwncqszvftbrmjlhg";
+        assert_eq!(Some(6), find_first_marker(message, 4));
+
+        let message = b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
+        assert_eq!(Some(10), find_first_marker(message, 4));
+
+        let message = b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
+        assert_eq!(Some(11), find_first_marker(message, 4));
+
+        let message = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        assert_eq!(Some(19), find_first_marker(message, 14));
+
+        let message = b"bvwbjplbgvbhsrlpgdmjqwftvncz";
+        assert_eq!(Some(23), find_first_marker(message, 14));
+
+        let message = b"nppdvjthqldpwncqszvftbrmjlhg";
+        assert_eq!(Some(23), find_first_marker(message, 14));
+
+        let message = b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
+        assert_eq!(Some(29), find_first_marker(message, 14));
+
+        let message = b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
+        assert_eq!(Some(26), find_first_marker(message, 14));
+    }
 
-        let message = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        assert_eq!(5, find_first_marker(message, 4));
+    #[test]
+    fn test_find_first_marker_matches_naive() {
+        let message = pseudo_random_stream(5000, 20);
+        for marker_length in 1..=20 {
+            let naive = message
+                .windows(marker_length)
+                .position(|window| window.iter().collect::<HashSet<_>>().len() == marker_length)
+                .map(|idx| idx + marker_length);
+            assert_eq!(naive, find_first_marker(&message, marker_length));
+        }
+    }
 
-        let message = "nppdvjthqldpwncqszvftbrmjlhg";
-        assert_eq!(6, find_first_marker(message, 4));
+    #[test]
+    #[ignore]
+    fn stress_find_first_marker() {
+        for marker_length in [4, 14, 100, 256, 257, 1000] {
+            // one symbol short of a marker, so the whole stream is scanned
+            let alphabet = (marker_length - 1).min(256);
+            let message = pseudo_random_stream(4 * 1024 * 1024, alphabet);
+            let start = std::time::Instant::now();
+            let marker = find_first_marker(&message, marker_length);
+            println!(
+                "marker length {} over {} bytes: {:?} in {:?}",
+                marker_length,
+                message.len(),
+                marker,
+                start.elapsed()
+            );
+        }
+    }
 
-        let message = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        assert_eq!(10, find_first_marker(message, 4));
+    #[test]
+    fn test_find_first_marker_not_found() {
+        assert_eq!(None, find_first_marker(b"abcabcabc", 4));
+        assert_eq!(None, find_first_marker(b"abc", 4));
+        assert_eq!(None, find_first_marker(b"", 1));
+        assert_eq!(Some(4), find_first_marker(b"aabc", 3));
+        assert_eq!(Some(0), find_first_marker(b"", 0));
+        assert_eq!(Some(6), find_first_marker("ééab".as_bytes(), 4));
+    }
 
-        let message = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
-        assert_eq!(11, find_first_marker(message, 4));
+    #[test]
+    fn test_marker_detector_feed() {
+        let message = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        for chunk_size in 1..=message.len() {
+            let mut detector = MarkerDetector::default();
+            let found: Vec<Marker> = message
+                .chunks(chunk_size)
+                .flat_map(|chunk| detector.feed(chunk))
+                .collect();
+            assert_eq!(
+                vec![Marker::StartOfPacket(7), Marker::StartOfMessage(19)],
+                found
+            );
+            assert_eq!(Some(7), detector.start_of_packet());
+            assert_eq!(Some(19), detector.start_of_message());
+        }
 
-        let message = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-        assert_eq!(19, find_first_marker(message, 14));
+        let mut detector = MarkerDetector::default();
+        assert_eq!(vec![Marker::StartOfPacket(4)], detector.feed(b"abcdab"));
+        assert_eq!(None, detector.start_of_message());
+        assert!(!detector.is_done());
+    }
 
-        let message = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        assert_eq!(23, find_first_marker(message, 14));
+    #[test]
+    fn test_marker_detector_empty_markers() {
+        let mut detector = MarkerDetector::new(0, 3);
+        assert_eq!(find_first_marker(b"", 0), detector.start_of_packet());
+        assert_eq!(vec![Marker::StartOfMessage(3)], detector.feed(b"abc"));
+        assert_eq!(Some(0), detector.start_of_packet());
 
-        let message = "nppdvjthqldpwncqszvftbrmjlhg";
-        assert_eq!(23, find_first_marker(message, 14));
+        let detector = MarkerDetector::new(0, 0);
+        assert!(detector.is_done());
+        assert_eq!(Some(0), detector.start_of_message());
+    }
 
-        let message = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        assert_eq!(29, find_first_marker(message, 14));
+    #[test]
+    fn test_detect_markers() {
+        let message = pseudo_random_stream(100_000, 16);
+        let detector = detect_markers(&message[..]).unwrap();
+        assert_eq!(find_first_marker(&message, 4), detector.start_of_packet());
+        assert_eq!(find_first_marker(&message, 14), detector.start_of_message());
+    }
 
-        let message = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
-        assert_eq!(26, find_first_marker(message, 14));
+    fn pseudo_random_stream(length: usize, alphabet: usize) -> Vec<u8> {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        (0..length).map(|_| rng.below(alphabet) as u8).collect()
     }
 }
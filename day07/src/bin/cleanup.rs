@@ -0,0 +1,40 @@
+use day07::{Filesystem, REQUIRED_SPACE, TOTAL_SPACE};
+use std::{env, fs::read_to_string};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let total_space = args
+        .first()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(TOTAL_SPACE);
+    let required_space = args
+        .get(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(REQUIRED_SPACE);
+    let path = args.get(2).map_or("./input/input.txt", |s| s.as_str());
+
+    let input = read_to_string(path).unwrap();
+    let fs = match Filesystem::from_transcript(&input) {
+        Ok(fs) => fs,
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                println!("{}", diagnostic);
+            }
+            return;
+        }
+    };
+
+    println!(
+        "need to free {}",
+        fs.space_to_free(total_space, required_space)
+    );
+    match fs.plan_cleanup(total_space, required_space) {
+        Some(plan) => {
+            for path in plan.paths {
+                println!("{}", path);
+            }
+            println!("frees {}", plan.freed);
+        }
+        None => println!("No set of directories frees enough space"),
+    }
+}
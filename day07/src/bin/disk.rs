@@ -0,0 +1,33 @@
+use day07::{transcript_from_directory, Filesystem};
+use std::{env, fs::read_to_string, path::Path};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["export", target, rest @ ..] => {
+            let path = rest.first().copied().unwrap_or("./input/input.txt");
+            let input = read_to_string(path).unwrap();
+            match Filesystem::from_transcript(&input) {
+                Ok(fs) => match fs.materialize(Path::new(target)) {
+                    Ok(()) => println!("Wrote filesystem to {}", target),
+                    Err(err) => println!("Cannot write to {}: {}", target, err),
+                },
+                Err(diagnostics) => {
+                    for diagnostic in diagnostics {
+                        println!("{}", diagnostic);
+                    }
+                }
+            }
+        }
+        ["import", source] => match transcript_from_directory(Path::new(source)) {
+            Ok(transcript) => print!("{}", transcript),
+            Err(err) => println!("Cannot read {}: {}", source, err),
+        },
+        _ => println!("Usage: disk export <target dir> [input] | disk import <source dir>"),
+    }
+}
@@ -2,7 +2,14 @@ use day07::process_part1;
 use std::fs::read_to_string;
 
 fn main() {
-  let input = read_to_string("./input/input.txt").unwrap();
-  let output = process_part1(&input);
-  println!("{}", output);  
-}
\ No newline at end of file
+    let input = read_to_string("./input/input.txt").unwrap();
+    let result = process_part1(&input);
+    match result {
+        Ok(output) => println!("{}", output),
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                println!("{}", diagnostic);
+            }
+        }
+    }
+}
@@ -2,7 +2,15 @@ use day07::process_part2;
 use std::fs::read_to_string;
 
 fn main() {
-  let input = read_to_string("./input/input.txt").unwrap();
-  let output = process_part2(&input);
-  println!("{}", output);  
-}
\ No newline at end of file
+    let input = read_to_string("./input/input.txt").unwrap();
+    let result = process_part2(&input);
+    match result {
+        Ok(Some(output)) => println!("{}", output),
+        Ok(None) => println!("No directory frees enough space"),
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                println!("{}", diagnostic);
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use day07::{Filesystem, Query};
+use std::{env, fs::read_to_string};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let query = match args.first().map(|s| s.parse::<Query>()) {
+        Some(Ok(query)) => query,
+        Some(Err(err)) => {
+            println!("{}", err);
+            return;
+        }
+        None => {
+            println!("Usage: query \"type=file size>1M path=/a/** | count\" [input]");
+            return;
+        }
+    };
+    let path = args.get(1).map_or("./input/input.txt", |s| s.as_str());
+
+    let input = read_to_string(path).unwrap();
+    match Filesystem::from_transcript(&input) {
+        Ok(fs) => println!("{}", fs.query(&query)),
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                println!("{}", diagnostic);
+            }
+        }
+    }
+}
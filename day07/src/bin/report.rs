@@ -0,0 +1,24 @@
+use day07::Filesystem;
+use std::{env, fs::read_to_string};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let path = args.get(1).map_or("./input/input.txt", |s| s.as_str());
+    let input = read_to_string(path).unwrap();
+    let fs = match Filesystem::from_transcript(&input) {
+        Ok(fs) => fs,
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                println!("{}", diagnostic);
+            }
+            return;
+        }
+    };
+
+    match args.first().map(|s| s.as_str()) {
+        Some("tree") => println!("{}", fs.render_tree()),
+        Some("du") => println!("{}", fs.render_du()),
+        Some("json") => println!("{}", fs.to_json()),
+        _ => println!("Usage: report <tree|du|json> [input]"),
+    }
+}
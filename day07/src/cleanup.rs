@@ -0,0 +1,330 @@
+use crate::{Filesystem, InodeId};
+use std::collections::HashSet;
+
+#[derive(PartialEq, Debug)]
+pub struct CleanupPlan {
+    pub freed: usize,
+    pub paths: Vec<String>,
+}
+
+impl Filesystem {
+    pub fn space_to_free(&self, total_space: usize, required_space: usize) -> usize {
+        let unused = total_space.saturating_sub(self.inode(self.root()).size());
+        required_space.saturating_sub(unused)
+    }
+
+    pub fn plan_cleanup(&self, total_space: usize, required_space: usize) -> Option<CleanupPlan> {
+        let needed = self.space_to_free(total_space, required_space);
+        let dirs: Vec<InodeId> = self
+            .flatten()
+            .into_iter()
+            .filter(|id| self.inode(*id).is_dir())
+            .collect();
+        let sizes: Vec<usize> = dirs.iter().map(|id| self.inode(*id).size()).collect();
+        let ends = subtree_ends(self, &dirs);
+
+        let chosen = if (dirs.len() + 1).saturating_mul(needed) <= TABLE_BITS {
+            plan_with_table(&sizes, &ends, needed)
+        } else {
+            plan_with_search(&sizes, &ends, needed)
+        }?;
+        Some(CleanupPlan {
+            freed: chosen.iter().map(|idx| sizes[*idx]).sum(),
+            paths: chosen.iter().map(|idx| self.path(dirs[*idx])).collect(),
+        })
+    }
+}
+
+// the table takes one bit per directory and byte to free, so larger
+// problems are searched instead
+const TABLE_BITS: usize = 1 << 27;
+
+// Directories are laid out in preorder, so the descendants of the one at
+// position i are exactly those before `ends[i]`: deleting it jumps there,
+// keeping it moves on to i + 1. Working backwards, `below[i]` holds every
+// amount short of `needed` that can be freed from position i on, and
+// `above[i]` the smallest amount that is enough.
+fn plan_with_table(sizes: &[usize], ends: &[usize], needed: usize) -> Option<Vec<usize>> {
+    let mut below = vec![Bitset::new(needed); sizes.len() + 1];
+    let mut above: Vec<Option<usize>> = vec![None; sizes.len() + 1];
+    if needed == 0 {
+        above[sizes.len()] = Some(0);
+    } else {
+        below[sizes.len()].set(0);
+    }
+
+    for idx in (0..sizes.len()).rev() {
+        let size = sizes[idx];
+        let end = ends[idx];
+        let taken_above = [
+            above[end].map(|freed| freed + size),
+            below[end]
+                .first_from(needed.saturating_sub(size))
+                .map(|freed| freed + size),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+
+        let mut reachable = below[idx + 1].clone();
+        reachable.or_shifted(&below[end], size);
+        below[idx] = reachable;
+        above[idx] = [above[idx + 1], taken_above].into_iter().flatten().min();
+    }
+
+    let mut target = above[0]?;
+    let mut idx = 0;
+    let mut chosen = Vec::new();
+    while target > 0 {
+        let kept = if target >= needed {
+            above[idx + 1] == Some(target)
+        } else {
+            below[idx + 1].get(target)
+        };
+        if kept {
+            idx += 1;
+        } else {
+            chosen.push(idx);
+            target -= sizes[idx];
+            idx = ends[idx];
+        }
+    }
+    Some(chosen)
+}
+
+// Depth-first over the same choices, deleting before keeping. A branch is
+// dropped once it cannot free enough any more, no longer beats the best plan
+// or arrives where an earlier one did with the same amount freed, and the
+// search stops at an exact fit.
+fn plan_with_search(sizes: &[usize], ends: &[usize], needed: usize) -> Option<Vec<usize>> {
+    if needed == 0 {
+        return Some(Vec::new());
+    }
+    let mut most = vec![0; sizes.len() + 1];
+    for idx in (0..sizes.len()).rev() {
+        most[idx] = most[idx + 1].max(sizes[idx] + most[ends[idx]]);
+    }
+
+    let mut best = None;
+    let mut best_freed = usize::MAX;
+    let mut chosen = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![(0, 0, 0)];
+    while let Some((idx, freed, num_chosen)) = stack.pop() {
+        chosen.truncate(num_chosen);
+        if best_freed == needed {
+            break;
+        }
+        if idx == sizes.len() || freed + most[idx] < needed || !seen.insert((idx, freed)) {
+            continue;
+        }
+
+        stack.push((idx + 1, freed, num_chosen));
+        let taken = freed + sizes[idx];
+        if taken < best_freed {
+            chosen.push(idx);
+            if taken >= needed {
+                best = Some(chosen.clone());
+                best_freed = taken;
+            } else {
+                stack.push((ends[idx], taken, num_chosen + 1));
+            }
+        }
+    }
+    best
+}
+
+#[derive(Clone)]
+struct Bitset {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    fn new(len: usize) -> Bitset {
+        Bitset {
+            len,
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn get(&self, bit: usize) -> bool {
+        bit < self.len && self.words[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    fn set(&mut self, bit: usize) {
+        self.words[bit / 64] |= 1 << (bit % 64);
+    }
+
+    fn first_from(&self, bit: usize) -> Option<usize> {
+        if bit >= self.len {
+            return None;
+        }
+        let mut word_idx = bit / 64;
+        let mut word = self.words[word_idx] & (u64::MAX << (bit % 64));
+        loop {
+            if word != 0 {
+                return Some(word_idx * 64 + word.trailing_zeros() as usize);
+            }
+            word_idx += 1;
+            word = *self.words.get(word_idx)?;
+        }
+    }
+
+    fn or_shifted(&mut self, other: &Bitset, shift: usize) {
+        if shift >= self.len {
+            return;
+        }
+        let (word_shift, bit_shift) = (shift / 64, shift % 64);
+        for idx in (word_shift..self.words.len()).rev() {
+            let src = idx - word_shift;
+            let mut word = other.words[src] << bit_shift;
+            if bit_shift > 0 && src > 0 {
+                word |= other.words[src - 1] >> (64 - bit_shift);
+            }
+            self.words[idx] |= word;
+        }
+        let tail = self.len % 64;
+        if tail > 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << tail) - 1;
+            }
+        }
+    }
+}
+
+fn subtree_ends(fs: &Filesystem, dirs: &[InodeId]) -> Vec<usize> {
+    let mut ends = vec![dirs.len(); dirs.len()];
+    let mut open: Vec<usize> = Vec::new();
+    for (idx, dir) in dirs.iter().enumerate() {
+        while let Some(last) = open.last() {
+            if fs.is_ancestor(dirs[*last], *dir) {
+                break;
+            }
+            ends[*last] = idx;
+            open.pop();
+        }
+        open.push(idx);
+    }
+    ends
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_example_fs1;
+
+    #[test]
+    fn test_plan_cleanup() {
+        let fs = create_example_fs1();
+        assert_eq!(
+            Some(CleanupPlan {
+                freed: 24933642,
+                paths: vec![String::from("/d")],
+            }),
+            fs.plan_cleanup(70000000, 30000000)
+        );
+        assert_eq!(
+            Some(CleanupPlan {
+                freed: 94853 + 24933642,
+                paths: vec![String::from("/a"), String::from("/d")],
+            }),
+            fs.plan_cleanup(48381165, 24933642 + 1000)
+        );
+        assert_eq!(
+            Some(CleanupPlan {
+                freed: 48381165,
+                paths: vec![String::from("/")],
+            }),
+            fs.plan_cleanup(48381165, 24933642 + 94853 + 1)
+        );
+        assert_eq!(None, fs.plan_cleanup(48381165, 48381166));
+    }
+
+    #[test]
+    fn test_plan_cleanup_nothing_needed() {
+        let fs = create_example_fs1();
+        assert_eq!(
+            Some(CleanupPlan {
+                freed: 0,
+                paths: vec![],
+            }),
+            fs.plan_cleanup(100000000, 30000000)
+        );
+    }
+
+    #[test]
+    fn test_plan_cleanup_prefers_exact_fit() {
+        let mut fs = Filesystem::new();
+        let root = fs.root();
+        for (name, size) in [("a", 6), ("b", 5), ("c", 4)] {
+            let dir = fs.add_dir(root, name).unwrap();
+            fs.add_file(dir, "f", size).unwrap();
+        }
+        let plan = fs.plan_cleanup(15, 9).unwrap();
+        assert_eq!(9, plan.freed);
+        assert_eq!(vec![String::from("/b"), String::from("/c")], plan.paths);
+    }
+
+    #[test]
+    fn test_search_matches_table() {
+        let fs = Filesystem::from_transcript(&nested_transcript(60)).unwrap();
+        let dirs: Vec<InodeId> = fs
+            .flatten()
+            .into_iter()
+            .filter(|id| fs.inode(*id).is_dir())
+            .collect();
+        let sizes: Vec<usize> = dirs.iter().map(|id| fs.inode(*id).size()).collect();
+        let ends = subtree_ends(&fs, &dirs);
+        let freed = |chosen: Option<Vec<usize>>| {
+            chosen.map(|chosen| chosen.iter().map(|idx| sizes[*idx]).sum::<usize>())
+        };
+
+        let total = sizes[0];
+        for needed in (0..=total + 1).step_by(331) {
+            assert_eq!(
+                freed(plan_with_table(&sizes, &ends, needed)),
+                freed(plan_with_search(&sizes, &ends, needed)),
+                "needed {}",
+                needed
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan_cleanup_large_requirement() {
+        // a table would take 21 × 30M bits here, so this is searched
+        let mut fs = Filesystem::new();
+        let root = fs.root();
+        for idx in 0..20 {
+            let dir = fs.add_dir(root, &format!("d{}", idx)).unwrap();
+            fs.add_file(dir, "f", (idx + 1) * 1_000_003).unwrap();
+        }
+        let used = fs.inode(root).size();
+        let plan = fs.plan_cleanup(used, 30 * 1_000_003).unwrap();
+        assert_eq!(30 * 1_000_003, plan.freed);
+        assert_eq!(7, plan.paths.len());
+    }
+
+    // nested directories holding files of a few hundred bytes each
+    fn nested_transcript(num_of_dirs: usize) -> String {
+        let mut lines = vec![String::from("$ cd /")];
+        for dir in 0..num_of_dirs {
+            lines.push(String::from("$ ls"));
+            lines.push(format!("dir d{}", dir));
+            for file in 0..dir % 3 {
+                lines.push(format!(
+                    "{} f{}-{}",
+                    (dir * 7919 + file * 104729) % 500 + 1,
+                    dir,
+                    file
+                ));
+            }
+            lines.push(format!("$ cd d{}", dir));
+            if dir % 4 == 3 {
+                lines.push(String::from("$ cd ../.."));
+            }
+        }
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{Filesystem, InodeId};
+
+impl Filesystem {
+    pub fn materialize(&self, target: &Path) -> io::Result<()> {
+        fs::create_dir_all(target)?;
+        self.materialize_content(self.root(), target)
+    }
+
+    fn materialize_content(&self, dir: InodeId, target: &Path) -> io::Result<()> {
+        for id in self.content(dir) {
+            let item = self.inode(*id);
+            let path = target.join(checked_name(item.name())?);
+            if item.is_dir() {
+                fs::create_dir_all(&path)?;
+                self.materialize_content(*id, &path)?;
+            } else {
+                fs::File::create(&path)?.set_len(item.size() as u64)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn transcript_from_directory(source: &Path) -> io::Result<String> {
+    let mut lines = vec![String::from("$ cd /")];
+    write_listing(source, &mut lines)?;
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+fn write_listing(dir: &Path, lines: &mut Vec<String>) -> io::Result<()> {
+    let mut entries: Vec<(String, PathBuf, fs::Metadata)> = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if !metadata.is_dir() && !metadata.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "cannot list {}, only files and directories fit a transcript",
+                    path.display()
+                ),
+            ));
+        }
+        let name = match entry.file_name().into_string() {
+            Ok(name) if !name.contains('\n') => name,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "cannot list {}, its name cannot be written on a transcript line",
+                        path.display()
+                    ),
+                ))
+            }
+        };
+        entries.push((name, path, metadata));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    lines.push(String::from("$ ls"));
+    for (name, _, metadata) in &entries {
+        if metadata.is_dir() {
+            lines.push(format!("dir {}", name));
+        } else {
+            lines.push(format!("{} {}", metadata.len(), name));
+        }
+    }
+
+    for (name, path, metadata) in &entries {
+        if metadata.is_dir() {
+            lines.push(format!("$ cd {}", name));
+            write_listing(path, lines)?;
+            lines.push(String::from("$ cd .."));
+        }
+    }
+    Ok(())
+}
+
+// names come from a transcript, so anything that would step outside the
+// target directory is refused
+fn checked_name(name: &str) -> io::Result<&str> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\0']) {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("refusing to create \"{}\"", name),
+        ))
+    } else {
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_example_fs1;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("day07-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn entries(fs: &Filesystem) -> Vec<(String, bool, usize)> {
+        let mut entries: Vec<_> = fs
+            .flatten()
+            .into_iter()
+            .map(|id| (fs.path(id), fs.inode(id).is_dir(), fs.inode(id).size()))
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn test_materialize_and_import() {
+        let dir = scratch_dir("round-trip");
+        let fs = create_example_fs1();
+        fs.materialize(&dir).unwrap();
+        assert_eq!(
+            8033020,
+            fs::metadata(dir.join("d").join("d.log")).unwrap().len()
+        );
+
+        let transcript = transcript_from_directory(&dir).unwrap();
+        let imported = Filesystem::from_transcript(&transcript).unwrap();
+        assert_eq!(entries(&fs), entries(&imported));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_import_names_with_spaces() {
+        let dir = scratch_dir("spaces");
+        fs::create_dir_all(dir.join("my dir").join("inner dir")).unwrap();
+        fs::write(dir.join("my dir").join("a file"), "hello").unwrap();
+        fs::write(dir.join("my dir").join("inner dir").join("b"), "hi").unwrap();
+
+        let transcript = transcript_from_directory(&dir).unwrap();
+        let imported = Filesystem::from_transcript(&transcript).unwrap();
+        assert_eq!(
+            vec![
+                (String::from("/"), true, 7),
+                (String::from("/my dir"), true, 7),
+                (String::from("/my dir/a file"), false, 5),
+                (String::from("/my dir/inner dir"), true, 2),
+                (String::from("/my dir/inner dir/b"), false, 2),
+            ],
+            entries(&imported)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_transcript_from_directory() {
+        let dir = scratch_dir("transcript");
+        fs::create_dir_all(dir.join("b").join("c")).unwrap();
+        fs::write(dir.join("a.txt"), "hello").unwrap();
+        fs::write(dir.join("b").join("c").join("d"), "").unwrap();
+
+        let expected = "\
+$ cd /
+$ ls
+5 a.txt
+dir b
+$ cd b
+$ ls
+dir c
+$ cd c
+$ ls
+0 d
+$ cd ..
+$ cd ..
+";
+        assert_eq!(expected, transcript_from_directory(&dir).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_transcript_refuses_unlistable_entries() {
+        let dir = scratch_dir("unlistable");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub").join("two\nlines"), "").unwrap();
+        let err = transcript_from_directory(&dir).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert!(err.to_string().contains("two\nlines"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        #[cfg(unix)]
+        {
+            let dir = scratch_dir("symlink");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("a"), "hello").unwrap();
+            std::os::unix::fs::symlink(dir.join("a"), dir.join("b")).unwrap();
+            let err = transcript_from_directory(&dir).unwrap_err();
+            assert_eq!(io::ErrorKind::Unsupported, err.kind());
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_materialize_refuses_escaping_names() {
+        let dir = scratch_dir("escape");
+        let mut fs = Filesystem::new();
+        let root = fs.root();
+        fs.add_file(root, "..", 5).unwrap();
+        assert!(fs.materialize(&dir).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,170 @@
+// The original copy-on-insert tree, kept to benchmark the arena against.
+
+use std::{rc::Rc, vec};
+
+pub(crate) fn process_part1_help(fs: &Inode) -> usize {
+    fs.flatten()
+        .iter()
+        .filter_map(|item| {
+            if item.is_dir() {
+                match item.size() {
+                    s if s <= 100000 => Some(s),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        })
+        .sum()
+}
+
+pub(crate) fn create_fs_from_commands(commands: Vec<&str>) -> Inode {
+    let mut root = Inode::Directory(Directory {
+        name: String::from("/"),
+        content: Vec::new(),
+    });
+    let mut path: Vec<&str> = Vec::new();
+    for cmd in commands {
+        let parts: Vec<&str> = cmd.split_whitespace().collect();
+        match parts.as_slice() {
+            ["$", "cd", "/"] => (),
+            ["$", "cd", ".."] => {
+                path.pop();
+            }
+            ["$", "cd", name] => path.push(name),
+            ["$", _] => (),
+            ["dir", name] => {
+                root = root.insert_item(
+                    &path,
+                    Rc::new(Inode::Directory(Directory::new(
+                        name.to_string(),
+                        Vec::new(),
+                    ))),
+                )
+            }
+            [size, name] => {
+                root = root.insert_item(
+                    &path,
+                    Rc::new(Inode::File(File::new(
+                        name.to_string(),
+                        size.parse().unwrap(),
+                    ))),
+                )
+            }
+            _ => (),
+        }
+    }
+
+    root
+}
+
+pub(crate) enum Inode {
+    File(File),
+    Directory(Directory),
+}
+
+impl Inode {
+    fn insert_item(&self, path: &[&str], item: Rc<Inode>) -> Inode {
+        match self {
+            Self::File(file) => Self::File(File::new(file.name.to_string(), file.size)),
+            Self::Directory(dir) => dir.insert_item(path, item),
+        }
+    }
+
+    fn is_dir(&self) -> bool {
+        match self {
+            Self::File(_) => false,
+            Self::Directory(_) => true,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Inode::File(file) => &file.name,
+            Inode::Directory(dir) => &dir.name,
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Self::File(file) => file.size(),
+            Self::Directory(dir) => dir.size(),
+        }
+    }
+
+    fn flatten(&self) -> Vec<Rc<Inode>> {
+        match self {
+            Self::File(_) => vec![Rc::new(Self::File(File::new(
+                self.name().to_string(),
+                self.size(),
+            )))],
+            Self::Directory(dir) => vec![Rc::new(Self::Directory(Directory::new(
+                self.name().to_string(),
+                dir.content.to_vec(),
+            )))]
+            .into_iter()
+            .chain(dir.content.iter().flat_map(|item| item.flatten()))
+            .collect(),
+        }
+    }
+}
+
+pub(crate) struct File {
+    name: String,
+    size: usize,
+}
+
+impl File {
+    fn new(name: String, size: usize) -> File {
+        File { name, size }
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+pub(crate) struct Directory {
+    name: String,
+    content: Vec<Rc<Inode>>,
+}
+
+impl Directory {
+    fn new(name: String, content: Vec<Rc<Inode>>) -> Directory {
+        Directory { name, content }
+    }
+
+    fn insert_item(&self, path: &[&str], item: Rc<Inode>) -> Inode {
+        match path.first() {
+            Some(first) => {
+                let content: Vec<Rc<Inode>> = self
+                    .content
+                    .iter()
+                    .map(|inode| {
+                        if inode.is_dir() && inode.name().eq(*first) {
+                            Rc::new(inode.insert_item(&path[1..], Rc::clone(&item)))
+                        } else {
+                            Rc::clone(inode)
+                        }
+                    })
+                    .collect();
+                Inode::Directory(Self {
+                    name: self.name.clone(),
+                    content,
+                })
+            }
+            None => {
+                let mut content: Vec<_> = self.content.to_vec();
+                content.push(Rc::clone(&item));
+                Inode::Directory(Self {
+                    name: self.name.clone(),
+                    content,
+                })
+            }
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.content.iter().map(|item| item.size()).sum()
+    }
+}
@@ -1,22 +1,71 @@
-use std::{rc::Rc, vec};
+mod cleanup;
+mod disk;
+#[cfg(test)]
+mod legacy;
+mod query;
+mod report;
+
+pub use cleanup::CleanupPlan;
+pub use disk::transcript_from_directory;
+pub use query::{Query, QueryError, QueryResult};
+
+use std::{collections::HashMap, fmt};
+
+pub const SMALL_DIR_LIMIT: usize = 100000;
+pub const TOTAL_SPACE: usize = 70000000;
+pub const REQUIRED_SPACE: usize = 30000000;
+
+pub fn process_part1(input: &str) -> Result<usize, Vec<Diagnostic>> {
+    let fs = create_fs_from_commands(input.lines().collect())?;
+    Ok(process_part1_help(&fs, SMALL_DIR_LIMIT))
+}
 
-pub fn process_part1(input: &str) -> usize {
-    let fs = create_fs_from_commands(input.lines().collect());
-    process_part1_help(&fs)
+pub fn process_part2(input: &str) -> Result<Option<usize>, Vec<Diagnostic>> {
+    let fs = create_fs_from_commands(input.lines().collect())?;
+    Ok(process_part2_help(&fs, TOTAL_SPACE, REQUIRED_SPACE))
 }
 
-pub fn process_part2(input: &str) -> usize {
-    let fs = create_fs_from_commands(input.lines().collect());
-    process_part2_help(&fs)
+#[derive(PartialEq, Debug)]
+pub enum Diagnostic {
+    UnknownCommand { line: usize, command: String },
+    MissingDirectory { line: usize, path: String },
+    MalformedLine { line: usize, text: String },
+    UnexpectedOutput { line: usize, text: String },
+    ConflictingEntry { line: usize, name: String },
 }
 
-fn process_part1_help(fs: &Inode) -> usize {
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Diagnostic::UnknownCommand { line, command } => {
+                write!(f, "line {}: unknown command \"{}\"", line, command)
+            }
+            Diagnostic::MissingDirectory { line, path } => {
+                write!(f, "line {}: no such directory \"{}\"", line, path)
+            }
+            Diagnostic::MalformedLine { line, text } => {
+                write!(f, "line {}: cannot parse \"{}\"", line, text)
+            }
+            Diagnostic::UnexpectedOutput { line, text } => {
+                write!(f, "line {}: \"{}\" is not the output of ls", line, text)
+            }
+            Diagnostic::ConflictingEntry { line, name } => write!(
+                f,
+                "line {}: \"{}\" was already listed differently",
+                line, name
+            ),
+        }
+    }
+}
+
+fn process_part1_help(fs: &Filesystem, max_size: usize) -> usize {
     fs.flatten()
         .iter()
+        .map(|id| fs.inode(*id))
         .filter_map(|item| {
             if item.is_dir() {
                 match item.size() {
-                    s if s <= 100000 => Some(s),
+                    s if s <= max_size => Some(s),
                     _ => None,
                 }
             } else {
@@ -26,10 +75,11 @@ fn process_part1_help(fs: &Inode) -> usize {
         .sum()
 }
 
-fn process_part2_help(fs: &Inode) -> usize {
-    let required_space = 30000000 - (70000000 - fs.size());
+fn process_part2_help(fs: &Filesystem, total_space: usize, required_space: usize) -> Option<usize> {
+    let required_space = fs.space_to_free(total_space, required_space);
     fs.flatten()
         .iter()
+        .map(|id| fs.inode(*id))
         .filter_map(|item| {
             if item.is_dir() {
                 match item.size() {
@@ -41,184 +91,307 @@ fn process_part2_help(fs: &Inode) -> usize {
             }
         })
         .min()
-        .unwrap()
 }
 
-fn create_fs_from_commands(commands: Vec<&str>) -> Inode {
-    let mut root = Inode::Directory(Directory {
-        name: String::from("/"),
-        content: Vec::new(),
-    });
-    let mut path: Vec<&str> = Vec::new();
-    for cmd in commands {
-        let parts: Vec<&str> = cmd.split_whitespace().collect();
-        match parts.as_slice() {
-            ["$", "cd", "/"] => (),
-            ["$", "cd", ".."] => {
-                path.pop();
+fn create_fs_from_commands(commands: Vec<&str>) -> Result<Filesystem, Vec<Diagnostic>> {
+    let (fs, diagnostics) = parse_transcript(&commands);
+    if diagnostics.is_empty() {
+        Ok(fs)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+fn parse_transcript(commands: &[&str]) -> (Filesystem, Vec<Diagnostic>) {
+    let mut fs = Filesystem::new();
+    let mut diagnostics = Vec::new();
+    let mut cwd = fs.root();
+    let mut listing = false;
+
+    for (idx, cmd) in commands.iter().enumerate() {
+        let line = idx + 1;
+        let cmd = cmd.trim();
+        if cmd.is_empty() {
+            continue;
+        }
+
+        if let Some(command) = cmd.strip_prefix('$') {
+            listing = false;
+            let command = command.trim_start();
+            // the rest of the line is the path, which may contain spaces
+            if let Some(path) = command.strip_prefix("cd ") {
+                let (dir, missing) = fs.resolve(cwd, path);
+                if missing {
+                    diagnostics.push(Diagnostic::MissingDirectory {
+                        line,
+                        path: path.to_string(),
+                    });
+                }
+                cwd = dir;
+                continue;
             }
-            ["$", "cd", name] => path.push(name),
-            ["$", _] => (),
-            ["dir", name] => {
-                root = root.insert_item(
-                    &path,
-                    Rc::new(Inode::Directory(Directory::new(
-                        name.to_string(),
-                        Vec::new(),
-                    ))),
-                )
+            let parts: Vec<&str> = command.split_whitespace().collect();
+            match parts.as_slice() {
+                ["ls"] => listing = true,
+                ["cd", ..] | ["ls", ..] | [] => diagnostics.push(Diagnostic::MalformedLine {
+                    line,
+                    text: cmd.to_string(),
+                }),
+                [command, ..] => diagnostics.push(Diagnostic::UnknownCommand {
+                    line,
+                    command: command.to_string(),
+                }),
             }
-            [size, name] => {
-                root = root.insert_item(
-                    &path,
-                    Rc::new(Inode::File(File::new(
-                        name.to_string(),
-                        size.parse().unwrap(),
-                    ))),
-                )
+            continue;
+        }
+
+        if !listing {
+            diagnostics.push(Diagnostic::UnexpectedOutput {
+                line,
+                text: cmd.to_string(),
+            });
+            continue;
+        }
+
+        let added = match cmd.split_once(' ') {
+            Some(("dir", name)) => fs.add_dir(cwd, name).map(|_| ()),
+            Some((size, name)) => match size.parse() {
+                Ok(size) => fs.add_file(cwd, name, size).map(|_| ()),
+                Err(_) => {
+                    diagnostics.push(Diagnostic::MalformedLine {
+                        line,
+                        text: cmd.to_string(),
+                    });
+                    continue;
+                }
+            },
+            None => {
+                diagnostics.push(Diagnostic::MalformedLine {
+                    line,
+                    text: cmd.to_string(),
+                });
+                continue;
             }
-            _ => (),
+        };
+        if let Err(name) = added {
+            diagnostics.push(Diagnostic::ConflictingEntry { line, name });
         }
     }
 
-    return root;
+    (fs, diagnostics)
 }
 
-enum Inode {
-    File(File),
-    Directory(Directory),
+type InodeId = usize;
+
+pub struct Filesystem {
+    inodes: Vec<Inode>,
 }
 
-impl Inode {
-    fn insert_item(&self, path: &[&str], item: Rc<Inode>) -> Inode {
-        match self {
-            Self::File(file) => Self::File(File::new(file.name.to_string(), file.size)),
-            Self::Directory(dir) => dir.insert_item(path, item),
+impl Filesystem {
+    pub fn from_transcript(input: &str) -> Result<Filesystem, Vec<Diagnostic>> {
+        create_fs_from_commands(input.lines().collect())
+    }
+
+    fn new() -> Filesystem {
+        Filesystem {
+            inodes: vec![Inode {
+                name: String::from("/"),
+                parent: None,
+                kind: Kind::Directory {
+                    content: Vec::new(),
+                    by_name: HashMap::new(),
+                    size: 0,
+                },
+            }],
         }
     }
 
-    fn is_same(&self, other: &Inode) -> bool {
-        match (self, other) {
-            (Self::File(this), Self::File(other)) => this.is_same(other),
-            (Self::Directory(this), Self::Directory(other)) => this.is_same(other),
-            _ => false,
+    fn root(&self) -> InodeId {
+        0
+    }
+
+    fn inode(&self, id: InodeId) -> &Inode {
+        &self.inodes[id]
+    }
+
+    fn parent(&self, id: InodeId) -> Option<InodeId> {
+        self.inodes[id].parent
+    }
+
+    fn content(&self, id: InodeId) -> &[InodeId] {
+        match &self.inodes[id].kind {
+            Kind::File { .. } => &[],
+            Kind::Directory { content, .. } => content,
         }
     }
 
-    fn is_dir(&self) -> bool {
-        match self {
-            Self::File(_) => false,
-            Self::Directory(_) => true,
+    fn path(&self, id: InodeId) -> String {
+        let mut names = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            names.push(self.inodes[current].name());
+            current = parent;
         }
+        names.reverse();
+        format!("/{}", names.join("/"))
     }
 
-    fn name(&self) -> &str {
-        match self {
-            Inode::File(file) => &file.name,
-            Inode::Directory(dir) => &dir.name,
+    fn depth(&self, id: InodeId) -> usize {
+        let mut depth = 0;
+        let mut current = self.parent(id);
+        while let Some(dir) = current {
+            depth += 1;
+            current = self.parent(dir);
         }
+        depth
     }
 
-    fn size(&self) -> usize {
-        match self {
-            Self::File(file) => file.size(),
-            Self::Directory(dir) => dir.size(),
+    fn is_ancestor(&self, ancestor: InodeId, id: InodeId) -> bool {
+        let mut current = self.parent(id);
+        while let Some(dir) = current {
+            if dir == ancestor {
+                return true;
+            }
+            current = self.parent(dir);
         }
+        false
     }
 
-    fn flatten(&self) -> Vec<Rc<Inode>> {
-        match self {
-            Self::File(_) => vec![Rc::new(Self::File(File::new(
-                self.name().to_string(),
-                self.size(),
-            )))],
-            Self::Directory(dir) => vec![Rc::new(Self::Directory(Directory::new(
-                self.name().to_string(),
-                dir.content.to_vec(),
-            )))]
-            .into_iter()
-            .chain(
-                dir.content
-                    .iter()
-                    .map(|item| item.flatten())
-                    .flat_map(|item| item),
-            )
-            .collect(),
+    fn child(&self, dir: InodeId, name: &str) -> Option<InodeId> {
+        match &self.inodes[dir].kind {
+            Kind::File { .. } => None,
+            Kind::Directory { by_name, .. } => by_name.get(name).copied(),
         }
     }
-}
 
-struct File {
-    name: String,
-    size: usize,
-}
+    fn child_dir(&self, dir: InodeId, name: &str) -> Option<InodeId> {
+        self.child(dir, name).filter(|id| self.inodes[*id].is_dir())
+    }
 
-impl File {
-    fn new(name: String, size: usize) -> File {
-        File { name, size }
+    // follows a cd argument, creating any directory that was never listed so
+    // the rest of the transcript still has somewhere to go
+    fn resolve(&mut self, cwd: InodeId, path: &str) -> (InodeId, bool) {
+        let mut dir = if path.starts_with('/') {
+            self.root()
+        } else {
+            cwd
+        };
+        let mut missing = false;
+        for part in path.split('/') {
+            dir = match part {
+                "" | "." => dir,
+                ".." => self.parent(dir).unwrap_or(dir),
+                name => match self.child_dir(dir, name) {
+                    Some(child) => child,
+                    None => {
+                        missing = true;
+                        self.add_dir(dir, name).unwrap_or(dir)
+                    }
+                },
+            };
+        }
+        (dir, missing)
     }
 
-    fn is_same(&self, other: &File) -> bool {
-        self.name.eq(&other.name) && self.size == other.size
+    fn add_dir(&mut self, parent: InodeId, name: &str) -> Result<InodeId, String> {
+        match self.child(parent, name) {
+            Some(id) if self.inodes[id].is_dir() => Ok(id),
+            Some(_) => Err(name.to_string()),
+            None => Ok(self.add_inode(
+                parent,
+                name,
+                Kind::Directory {
+                    content: Vec::new(),
+                    by_name: HashMap::new(),
+                    size: 0,
+                },
+            )),
+        }
     }
 
-    fn size(&self) -> usize {
-        self.size
+    fn add_file(&mut self, parent: InodeId, name: &str, size: usize) -> Result<InodeId, String> {
+        match self.child(parent, name) {
+            Some(id) if !self.inodes[id].is_dir() && self.inodes[id].size() == size => {
+                return Ok(id)
+            }
+            Some(_) => return Err(name.to_string()),
+            None => (),
+        }
+
+        let id = self.add_inode(parent, name, Kind::File { size });
+        let mut ancestor = Some(parent);
+        while let Some(dir) = ancestor {
+            if let Kind::Directory { size: dir_size, .. } = &mut self.inodes[dir].kind {
+                *dir_size += size;
+            }
+            ancestor = self.inodes[dir].parent;
+        }
+        Ok(id)
+    }
+
+    fn add_inode(&mut self, parent: InodeId, name: &str, kind: Kind) -> InodeId {
+        let id = self.inodes.len();
+        self.inodes.push(Inode {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+        });
+        if let Kind::Directory {
+            content, by_name, ..
+        } = &mut self.inodes[parent].kind
+        {
+            content.push(id);
+            by_name.insert(name.to_string(), id);
+        }
+        id
+    }
+
+    fn flatten(&self) -> Vec<InodeId> {
+        let mut items = Vec::with_capacity(self.inodes.len());
+        let mut stack = vec![self.root()];
+        while let Some(id) = stack.pop() {
+            items.push(id);
+            stack.extend(self.content(id).iter().rev());
+        }
+        items
     }
 }
 
-struct Directory {
+struct Inode {
     name: String,
-    content: Vec<Rc<Inode>>,
+    parent: Option<InodeId>,
+    kind: Kind,
 }
 
-impl Directory {
-    fn new(name: String, content: Vec<Rc<Inode>>) -> Directory {
-        Directory { name, content }
-    }
+enum Kind {
+    File {
+        size: usize,
+    },
+    // content keeps the listing order, by_name finds children by name
+    Directory {
+        content: Vec<InodeId>,
+        by_name: HashMap<String, InodeId>,
+        size: usize,
+    },
+}
 
-    fn insert_item(&self, path: &[&str], item: Rc<Inode>) -> Inode {
-        match path.first() {
-            Some(first) => {
-                let content: Vec<Rc<Inode>> = self
-                    .content
-                    .iter()
-                    .map(|inode| {
-                        if inode.is_dir() && inode.name().eq(*first) {
-                            Rc::new(inode.insert_item(&path[1..], Rc::clone(&item)))
-                        } else {
-                            Rc::clone(inode)
-                        }
-                    })
-                    .collect();
-                Inode::Directory(Self {
-                    name: self.name.clone(),
-                    content: content,
-                })
-            }
-            None => {
-                let mut content: Vec<_> = self.content.to_vec();
-                content.push(Rc::clone(&item));
-                Inode::Directory(Self {
-                    name: self.name.clone(),
-                    content: content,
-                })
-            }
+impl Inode {
+    fn is_dir(&self) -> bool {
+        match self.kind {
+            Kind::File { .. } => false,
+            Kind::Directory { .. } => true,
         }
     }
 
-    fn is_same(&self, other: &Directory) -> bool {
-        self.name.eq(&other.name)
-            && self.content.len() == other.content.len()
-            && self
-                .content
-                .iter()
-                .zip(other.content.iter())
-                .all(|(item1, item2)| item1.is_same(item2))
+    fn name(&self) -> &str {
+        &self.name
     }
 
     fn size(&self) -> usize {
-        self.content.iter().map(|item| item.size()).sum()
+        match self.kind {
+            Kind::File { size } => size,
+            Kind::Directory { size, .. } => size,
+        }
     }
 }
 
@@ -226,16 +399,41 @@ impl Directory {
 mod tests {
     use super::*;
 
+    impl Filesystem {
+        fn is_same(&self, other: &Filesystem) -> bool {
+            self.is_same_inode(self.root(), other, other.root())
+        }
+
+        fn is_same_inode(&self, id: InodeId, other: &Filesystem, other_id: InodeId) -> bool {
+            let (this, that) = (self.inode(id), other.inode(other_id));
+            this.name == that.name
+                && this.is_dir() == that.is_dir()
+                && this.size() == that.size()
+                && self.content(id).len() == other.content(other_id).len()
+                && self
+                    .content(id)
+                    .iter()
+                    .zip(other.content(other_id).iter())
+                    .all(|(item1, item2)| self.is_same_inode(*item1, other, *item2))
+        }
+    }
+
     #[test]
     fn test_process_part1_help() {
         let fs = create_example_fs1();
-        assert_eq!(95437, process_part1_help(&fs));
+        assert_eq!(95437, process_part1_help(&fs, SMALL_DIR_LIMIT));
+        assert_eq!(94853 + 584, process_part1_help(&fs, 94853));
     }
 
     #[test]
     fn test_process_part2_help() {
         let fs = create_example_fs1();
-        assert_eq!(24933642, process_part2_help(&fs));
+        assert_eq!(
+            Some(24933642),
+            process_part2_help(&fs, TOTAL_SPACE, REQUIRED_SPACE)
+        );
+        assert_eq!(Some(94853), process_part2_help(&fs, 48381165, 94000));
+        assert_eq!(None, process_part2_help(&fs, 48381165, 48381166));
     }
 
     #[test]
@@ -265,15 +463,128 @@ mod tests {
             "5626152 d.ext",
             "7214296 k",
         ];
-        let fs1 = create_fs_from_commands(commands);
+        let fs1 = create_fs_from_commands(commands).unwrap();
         let fs2 = create_example_fs1();
         assert!(fs1.is_same(&fs2));
     }
 
+    #[test]
+    fn test_parse_transcript_paths_and_relisting() {
+        let commands = vec![
+            "$ cd /",
+            "$ ls",
+            "dir a",
+            "14848514 b.txt",
+            "$ cd /a",
+            "$ ls",
+            "dir e",
+            "29116 f",
+            "$ cd e",
+            "$ ls",
+            "584 i",
+            "$ cd /",
+            "$ ls",
+            "dir a",
+            "14848514 b.txt",
+            "$ cd a/e/..",
+            "$ ls",
+            "dir e",
+            "29116 f",
+            "",
+        ];
+        let (fs, diagnostics) = parse_transcript(&commands);
+        assert_eq!(Vec::<Diagnostic>::new(), diagnostics);
+        assert_eq!(6, fs.flatten().len());
+        assert_eq!(14848514 + 29116 + 584, fs.inode(fs.root()).size());
+    }
+
+    #[test]
+    fn test_parse_transcript_names_with_spaces() {
+        let commands = vec![
+            "$ cd /",
+            "$ ls",
+            "dir my dir",
+            "$ cd my dir",
+            "$ ls",
+            "12 my file.txt",
+            "$ cd ..",
+            "$ cd /my dir",
+        ];
+        let (fs, diagnostics) = parse_transcript(&commands);
+        assert_eq!(Vec::<Diagnostic>::new(), diagnostics);
+        let dir = fs.child_dir(fs.root(), "my dir").unwrap();
+        assert_eq!(12, fs.inode(dir).size());
+        assert_eq!("/my dir/my file.txt", fs.path(fs.content(dir)[0]));
+    }
+
+    #[test]
+    fn test_parse_transcript_diagnostics() {
+        let commands = vec![
+            "$ cd /",
+            "dir a",
+            "$ ls",
+            "dir a",
+            "12 b",
+            "x c",
+            "garbage",
+            "$ rm b",
+            "$ cd",
+            "$ cd missing",
+            "$ ls",
+            "5 d",
+            "$ cd /",
+            "$ ls",
+            "13 b",
+            "dir b",
+        ];
+        let (fs, diagnostics) = parse_transcript(&commands);
+        assert_eq!(
+            vec![
+                Diagnostic::UnexpectedOutput {
+                    line: 2,
+                    text: String::from("dir a")
+                },
+                Diagnostic::MalformedLine {
+                    line: 6,
+                    text: String::from("x c")
+                },
+                Diagnostic::MalformedLine {
+                    line: 7,
+                    text: String::from("garbage")
+                },
+                Diagnostic::UnknownCommand {
+                    line: 8,
+                    command: String::from("rm")
+                },
+                Diagnostic::MalformedLine {
+                    line: 9,
+                    text: String::from("$ cd")
+                },
+                Diagnostic::MissingDirectory {
+                    line: 10,
+                    path: String::from("missing")
+                },
+                Diagnostic::ConflictingEntry {
+                    line: 15,
+                    name: String::from("b")
+                },
+                Diagnostic::ConflictingEntry {
+                    line: 16,
+                    name: String::from("b")
+                },
+            ],
+            diagnostics
+        );
+        assert_eq!(17, fs.inode(fs.root()).size());
+        assert!(create_fs_from_commands(commands).is_err());
+    }
+
     #[test]
     fn test_size() {
         let fs = create_example_fs1();
-        assert_eq!(48381165, fs.size())
+        assert_eq!(48381165, fs.inode(fs.root()).size());
+        let a = fs.child_dir(fs.root(), "a").unwrap();
+        assert_eq!(94853, fs.inode(a).size());
     }
 
     #[test]
@@ -283,154 +594,122 @@ mod tests {
     }
 
     #[test]
-    fn test_insert_item() {
-        let fs2 = create_example_fs2();
-        let item = Rc::new(Inode::File(File {
-            name: String::from("i"),
-            size: 5,
-        }));
-        let fs3 = fs2.insert_item(&vec!["c", "f"], item);
-        assert!(fs3.is_same(&create_example_fs3()));
-    }
-
-    fn create_example_fs1() -> Inode {
-        Inode::Directory(Directory {
-            name: String::from("/"),
-            content: vec![
-                Rc::new(Inode::Directory(Directory {
-                    name: String::from("a"),
-                    content: vec![
-                        Rc::new(Inode::Directory(Directory {
-                            name: String::from("e"),
-                            content: vec![Rc::new(Inode::File(File {
-                                name: String::from("i"),
-                                size: 584,
-                            }))],
-                        })),
-                        Rc::new(Inode::File(File {
-                            name: String::from("f"),
-                            size: 29116,
-                        })),
-                        Rc::new(Inode::File(File {
-                            name: String::from("g"),
-                            size: 2557,
-                        })),
-                        Rc::new(Inode::File(File {
-                            name: String::from("h.lst"),
-                            size: 62596,
-                        })),
-                    ],
-                })),
-                Rc::new(Inode::File(File {
-                    name: String::from("b.txt"),
-                    size: 14848514,
-                })),
-                Rc::new(Inode::File(File {
-                    name: String::from("c.dat"),
-                    size: 8504156,
-                })),
-                Rc::new(Inode::Directory(Directory {
-                    name: String::from("d"),
-                    content: vec![
-                        Rc::new(Inode::File(File {
-                            name: String::from("j"),
-                            size: 4060174,
-                        })),
-                        Rc::new(Inode::File(File {
-                            name: String::from("d.log"),
-                            size: 8033020,
-                        })),
-                        Rc::new(Inode::File(File {
-                            name: String::from("d.ext"),
-                            size: 5626152,
-                        })),
-                        Rc::new(Inode::File(File {
-                            name: String::from("k"),
-                            size: 7214296,
-                        })),
-                    ],
-                })),
-            ],
-        })
+    fn test_add_file() {
+        let mut fs2 = create_example_fs2();
+        let c = fs2.child_dir(fs2.root(), "c").unwrap();
+        let f = fs2.child_dir(c, "f").unwrap();
+        fs2.add_file(f, "i", 5).unwrap();
+        assert!(fs2.is_same(&create_example_fs3()));
+        assert_eq!(Some(c), fs2.parent(f));
     }
 
-    fn create_example_fs2() -> Inode {
-        Inode::Directory(Directory {
-            name: String::from("a"),
-            content: vec![
-                Rc::new(Inode::File(File {
-                    name: String::from("b"),
-                    size: 5,
-                })),
-                Rc::new(Inode::Directory(Directory {
-                    name: String::from("c"),
-                    content: vec![
-                        Rc::new(Inode::File(File {
-                            name: String::from("e"),
-                            size: 5,
-                        })),
-                        Rc::new(Inode::Directory(Directory {
-                            name: String::from("f"),
-                            content: vec![
-                                Rc::new(Inode::File(File {
-                                    name: String::from("g"),
-                                    size: 5,
-                                })),
-                                Rc::new(Inode::File(File {
-                                    name: String::from("h"),
-                                    size: 5,
-                                })),
-                            ],
-                        })),
-                    ],
-                })),
-                Rc::new(Inode::File(File {
-                    name: String::from("d"),
-                    size: 5,
-                })),
-            ],
-        })
+    #[test]
+    fn test_path() {
+        let fs = create_example_fs1();
+        let a = fs.child_dir(fs.root(), "a").unwrap();
+        let e = fs.child_dir(a, "e").unwrap();
+        assert_eq!("/", fs.path(fs.root()));
+        assert_eq!("/a/e", fs.path(e));
+        assert_eq!("/a/e/i", fs.path(fs.child(e, "i").unwrap()));
     }
 
-    fn create_example_fs3() -> Inode {
-        Inode::Directory(Directory {
-            name: String::from("a"),
-            content: vec![
-                Rc::new(Inode::File(File {
-                    name: String::from("b"),
-                    size: 5,
-                })),
-                Rc::new(Inode::Directory(Directory {
-                    name: String::from("c"),
-                    content: vec![
-                        Rc::new(Inode::File(File {
-                            name: String::from("e"),
-                            size: 5,
-                        })),
-                        Rc::new(Inode::Directory(Directory {
-                            name: String::from("f"),
-                            content: vec![
-                                Rc::new(Inode::File(File {
-                                    name: String::from("g"),
-                                    size: 5,
-                                })),
-                                Rc::new(Inode::File(File {
-                                    name: String::from("h"),
-                                    size: 5,
-                                })),
-                                Rc::new(Inode::File(File {
-                                    name: String::from("i"),
-                                    size: 5,
-                                })),
-                            ],
-                        })),
-                    ],
-                })),
-                Rc::new(Inode::File(File {
-                    name: String::from("d"),
-                    size: 5,
-                })),
-            ],
-        })
+    #[test]
+    fn test_flatten() {
+        let fs = create_example_fs2();
+        let names: Vec<&str> = fs.flatten().iter().map(|id| fs.inode(*id).name()).collect();
+        assert_eq!(vec!["/", "b", "c", "e", "f", "g", "h", "d"], names);
+    }
+
+    #[test]
+    fn test_wide_directory() {
+        let mut commands = vec![String::from("$ cd /"), String::from("$ ls")];
+        commands.extend((0..20_000).map(|idx| format!("{} f{}", idx + 1, idx)));
+        commands.push(String::from("$ ls"));
+        commands.extend((0..20_000).map(|idx| format!("{} f{}", idx + 1, idx)));
+        let commands: Vec<&str> = commands.iter().map(|cmd| cmd.as_str()).collect();
+
+        let fs = create_fs_from_commands(commands).unwrap();
+        assert_eq!(20_001, fs.flatten().len());
+        assert_eq!(20_000 * 20_001 / 2, fs.inode(fs.root()).size());
+        assert_eq!(Some(5), fs.child(fs.root(), "f4"));
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_deep_listing() {
+        let commands = deep_listing(2000, 5);
+        let commands: Vec<&str> = commands.iter().map(|cmd| cmd.as_str()).collect();
+
+        let start = std::time::Instant::now();
+        let fs = create_fs_from_commands(commands.clone()).unwrap();
+        let arena = process_part1_help(&fs, SMALL_DIR_LIMIT);
+        println!("arena: {:?}", start.elapsed());
+
+        let start = std::time::Instant::now();
+        let fs = legacy::create_fs_from_commands(commands);
+        let copying = legacy::process_part1_help(&fs);
+        println!("copy on insert: {:?}", start.elapsed());
+
+        assert_eq!(copying, arena);
+    }
+
+    fn deep_listing(depth: usize, files_per_dir: usize) -> Vec<String> {
+        let mut commands = vec![String::from("$ cd /")];
+        for level in 0..depth {
+            commands.push(String::from("$ ls"));
+            commands.push(format!("dir d{}", level));
+            for file in 0..files_per_dir {
+                commands.push(format!("{} f{}", level * 10 + file + 1, file));
+            }
+            commands.push(format!("$ cd d{}", level));
+        }
+        commands.extend((0..depth).map(|_| String::from("$ cd ..")));
+        commands
+    }
+
+    pub(crate) fn create_example_fs1() -> Filesystem {
+        let mut fs = Filesystem::new();
+        let root = fs.root();
+        let a = fs.add_dir(root, "a").unwrap();
+        let e = fs.add_dir(a, "e").unwrap();
+        fs.add_file(e, "i", 584).unwrap();
+        fs.add_file(a, "f", 29116).unwrap();
+        fs.add_file(a, "g", 2557).unwrap();
+        fs.add_file(a, "h.lst", 62596).unwrap();
+        fs.add_file(root, "b.txt", 14848514).unwrap();
+        fs.add_file(root, "c.dat", 8504156).unwrap();
+        let d = fs.add_dir(root, "d").unwrap();
+        fs.add_file(d, "j", 4060174).unwrap();
+        fs.add_file(d, "d.log", 8033020).unwrap();
+        fs.add_file(d, "d.ext", 5626152).unwrap();
+        fs.add_file(d, "k", 7214296).unwrap();
+        fs
+    }
+
+    fn create_example_fs2() -> Filesystem {
+        let mut fs = Filesystem::new();
+        let root = fs.root();
+        fs.add_file(root, "b", 5).unwrap();
+        let c = fs.add_dir(root, "c").unwrap();
+        fs.add_file(c, "e", 5).unwrap();
+        let f = fs.add_dir(c, "f").unwrap();
+        fs.add_file(f, "g", 5).unwrap();
+        fs.add_file(f, "h", 5).unwrap();
+        fs.add_file(root, "d", 5).unwrap();
+        fs
+    }
+
+    fn create_example_fs3() -> Filesystem {
+        let mut fs = Filesystem::new();
+        let root = fs.root();
+        fs.add_file(root, "b", 5).unwrap();
+        let c = fs.add_dir(root, "c").unwrap();
+        fs.add_file(c, "e", 5).unwrap();
+        let f = fs.add_dir(c, "f").unwrap();
+        fs.add_file(f, "g", 5).unwrap();
+        fs.add_file(f, "h", 5).unwrap();
+        fs.add_file(f, "i", 5).unwrap();
+        fs.add_file(root, "d", 5).unwrap();
+        fs
     }
 }
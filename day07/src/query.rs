@@ -0,0 +1,307 @@
+use std::{fmt, str::FromStr};
+
+use crate::{Filesystem, InodeId};
+
+#[derive(PartialEq, Debug)]
+pub struct Query {
+    predicates: Vec<Predicate>,
+    aggregate: Option<Aggregate>,
+}
+
+#[derive(PartialEq, Debug)]
+enum Predicate {
+    IsDir(bool),
+    Path(String),
+    Name(String),
+    Size(Comparison, usize),
+    Contains(String),
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Aggregate {
+    Count,
+    Sum,
+    Largest,
+    Smallest,
+    Deepest,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum QueryResult {
+    Paths(Vec<String>),
+    Number(usize),
+    Path(Option<String>),
+}
+
+#[derive(PartialEq, Debug)]
+pub struct QueryError(String);
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid query: {}", self.0)
+    }
+}
+
+impl fmt::Display for QueryResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryResult::Paths(paths) => write!(f, "{}", paths.join("\n")),
+            QueryResult::Number(number) => write!(f, "{}", number),
+            QueryResult::Path(Some(path)) => write!(f, "{}", path),
+            QueryResult::Path(None) => write!(f, "nothing matched"),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (filter, aggregate) = match s.split_once('|') {
+            Some((filter, aggregate)) => (filter, Some(parse_aggregate(aggregate.trim())?)),
+            None => (s, None),
+        };
+        let predicates = filter
+            .split_whitespace()
+            .map(parse_predicate)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Query {
+            predicates,
+            aggregate,
+        })
+    }
+}
+
+fn parse_aggregate(s: &str) -> Result<Aggregate, QueryError> {
+    match s {
+        "count" => Ok(Aggregate::Count),
+        "sum" => Ok(Aggregate::Sum),
+        "largest" => Ok(Aggregate::Largest),
+        "smallest" => Ok(Aggregate::Smallest),
+        "deepest" => Ok(Aggregate::Deepest),
+        _ => Err(QueryError(format!("unknown aggregate \"{}\"", s))),
+    }
+}
+
+fn parse_predicate(s: &str) -> Result<Predicate, QueryError> {
+    let invalid = || QueryError(format!("cannot parse \"{}\"", s));
+    if let Some(rest) = s.strip_prefix("size") {
+        let (comparison, value) = [
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            ("=", Comparison::Equal),
+        ]
+        .iter()
+        .find_map(|(op, comparison)| rest.strip_prefix(op).map(|value| (*comparison, value)))
+        .ok_or_else(invalid)?;
+        return Ok(Predicate::Size(
+            comparison,
+            parse_size(value).ok_or_else(invalid)?,
+        ));
+    }
+
+    match s.split_once('=') {
+        Some(("type", "dir")) => Ok(Predicate::IsDir(true)),
+        Some(("type", "file")) => Ok(Predicate::IsDir(false)),
+        Some(("path", glob)) => Ok(Predicate::Path(glob.to_string())),
+        Some(("name", glob)) => Ok(Predicate::Name(glob.to_string())),
+        Some(("contains", glob)) => Ok(Predicate::Contains(glob.to_string())),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_size(s: &str) -> Option<usize> {
+    let (number, multiplier) = match s.char_indices().last()? {
+        (idx, 'K') => (&s[..idx], 1 << 10),
+        (idx, 'M') => (&s[..idx], 1 << 20),
+        (idx, 'G') => (&s[..idx], 1 << 30),
+        _ => (s, 1),
+    };
+    number.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+impl Filesystem {
+    pub fn query(&self, query: &Query) -> QueryResult {
+        let selected = self.select(query);
+        match query.aggregate {
+            None => QueryResult::Paths(selected.iter().map(|id| self.path(*id)).collect()),
+            Some(Aggregate::Count) => QueryResult::Number(selected.len()),
+            Some(Aggregate::Sum) => {
+                QueryResult::Number(selected.iter().map(|id| self.inode(*id).size()).sum())
+            }
+            Some(Aggregate::Largest) => QueryResult::Path(
+                selected
+                    .iter()
+                    .max_by_key(|id| self.inode(**id).size())
+                    .map(|id| self.path(*id)),
+            ),
+            Some(Aggregate::Smallest) => QueryResult::Path(
+                selected
+                    .iter()
+                    .min_by_key(|id| self.inode(**id).size())
+                    .map(|id| self.path(*id)),
+            ),
+            Some(Aggregate::Deepest) => QueryResult::Path(
+                selected
+                    .iter()
+                    .max_by_key(|id| self.depth(**id))
+                    .map(|id| self.path(*id)),
+            ),
+        }
+    }
+
+    fn select(&self, query: &Query) -> Vec<InodeId> {
+        self.flatten()
+            .into_iter()
+            .filter(|id| {
+                query
+                    .predicates
+                    .iter()
+                    .all(|predicate| self.matches(*id, predicate))
+            })
+            .collect()
+    }
+
+    fn matches(&self, id: InodeId, predicate: &Predicate) -> bool {
+        let item = self.inode(id);
+        match predicate {
+            Predicate::IsDir(is_dir) => item.is_dir() == *is_dir,
+            Predicate::Path(glob) => glob_matches(glob, &self.path(id), true),
+            Predicate::Name(glob) => glob_matches(glob, item.name(), false),
+            Predicate::Size(comparison, value) => match comparison {
+                Comparison::Less => item.size() < *value,
+                Comparison::LessOrEqual => item.size() <= *value,
+                Comparison::Greater => item.size() > *value,
+                Comparison::GreaterOrEqual => item.size() >= *value,
+                Comparison::Equal => item.size() == *value,
+            },
+            Predicate::Contains(glob) => self
+                .content(id)
+                .iter()
+                .any(|child| glob_matches(glob, self.inode(*child).name(), false)),
+        }
+    }
+}
+
+// `?` and `*` never match a `/` in paths, `**` matches across them
+fn glob_matches(glob: &str, text: &str, is_path: bool) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (glob_len, text_len) = (glob.len(), text.len());
+    let within_segment = |c: &char| !is_path || *c != '/';
+
+    // matches[g][t] tells whether glob[g..] matches text[t..]; stars either
+    // stop or swallow one more character, so every cell is computed once.
+    // `**/` skips up to any later `/`, tracked in after_slash as t goes down.
+    let mut matches = vec![vec![false; text_len + 1]; glob_len + 1];
+    matches[glob_len][text_len] = true;
+    for g in (0..glob_len).rev() {
+        let mut after_slash = false;
+        for t in (0..=text_len).rev() {
+            let next = text.get(t);
+            matches[g][t] = match &glob[g..] {
+                ['*', '*', '/', ..] => {
+                    after_slash |= next == Some(&'/') && matches[g + 3][t + 1];
+                    matches[g + 3][t] || after_slash
+                }
+                ['*', '*', ..] => matches[g + 2][t] || (next.is_some() && matches[g][t + 1]),
+                ['*', ..] => {
+                    matches[g + 1][t] || (next.is_some_and(within_segment) && matches[g][t + 1])
+                }
+                ['?', ..] => next.is_some_and(within_segment) && matches[g + 1][t + 1],
+                [c, ..] => next == Some(c) && matches[g + 1][t + 1],
+                [] => unreachable!(),
+            };
+        }
+    }
+    matches[0][0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_example_fs1;
+
+    fn run(query: &str) -> QueryResult {
+        create_example_fs1().query(&query.parse().unwrap())
+    }
+
+    fn paths(paths: &[&str]) -> QueryResult {
+        QueryResult::Paths(paths.iter().map(|path| path.to_string()).collect())
+    }
+
+    #[test]
+    fn test_query_filters() {
+        assert_eq!(
+            paths(&["/b.txt", "/c.dat", "/d/j", "/d/d.log", "/d/d.ext", "/d/k"]),
+            run("type=file size>1M")
+        );
+        assert_eq!(
+            paths(&["/a/e/i", "/a/f", "/a/g", "/a/h.lst"]),
+            run("type=file path=/a/**")
+        );
+        assert_eq!(paths(&["/a/f", "/a/g"]), run("path=/a/? type=file"));
+        assert_eq!(paths(&["/d"]), run("type=dir contains=*.log"));
+        assert_eq!(paths(&["/a/h.lst", "/d/d.log"]), run("name=?.l*"));
+        assert_eq!(paths(&["/a/e", "/a/e/i"]), run("path=**/e**"));
+    }
+
+    #[test]
+    fn test_query_aggregates() {
+        assert_eq!(QueryResult::Number(2), run("type=dir size<=100000 | count"));
+        assert_eq!(
+            QueryResult::Number(95437),
+            run("type=dir size<=100000 | sum")
+        );
+        assert_eq!(
+            QueryResult::Path(Some(String::from("/b.txt"))),
+            run("type=file | largest")
+        );
+        assert_eq!(
+            QueryResult::Path(Some(String::from("/a/e/i"))),
+            run("| deepest")
+        );
+        assert_eq!(QueryResult::Path(None), run("size>1G | smallest"));
+    }
+
+    #[test]
+    fn test_parse_query_errors() {
+        assert!("type=link".parse::<Query>().is_err());
+        assert!("size>lots".parse::<Query>().is_err());
+        assert!("size>1M | median".parse::<Query>().is_err());
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("/a/*", "/a/b", true));
+        assert!(!glob_matches("/a/*", "/a/b/c", true));
+        assert!(glob_matches("/a/**", "/a/b/c", true));
+        assert!(glob_matches("/**/c", "/c", true));
+        assert!(glob_matches("/**/c", "/a/b/c", true));
+        assert!(!glob_matches("/**/c", "/a/bc", true));
+        assert!(glob_matches("*.log", "d.log", false));
+        assert!(!glob_matches("*.log", "d.logs", false));
+        assert!(glob_matches("", "", false));
+        assert!(!glob_matches("a?", "a", false));
+        assert!(!glob_matches("/?", "//", true));
+    }
+
+    #[test]
+    fn test_glob_matches_many_stars() {
+        let text = "a".repeat(200);
+        assert!(!glob_matches("*a*a*a*a*a*a*a*a*a*a*b", &text, false));
+        assert!(!glob_matches("**a**a**a**a**a**a**a**b", &text, true));
+        assert!(glob_matches("*a*a*a*a*a*a*a*a*a*a*", &text, false));
+    }
+}
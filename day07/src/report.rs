@@ -0,0 +1,181 @@
+use crate::{Filesystem, InodeId};
+
+impl Filesystem {
+    pub fn render_tree(&self) -> String {
+        let root = self.inode(self.root());
+        let mut lines = vec![format!("{} ({})", root.name(), root.size())];
+        self.render_tree_content(self.root(), "", &mut lines);
+        lines.join("\n")
+    }
+
+    fn render_tree_content(&self, dir: InodeId, prefix: &str, lines: &mut Vec<String>) {
+        let content = self.content(dir);
+        for (idx, id) in content.iter().enumerate() {
+            let is_last = idx + 1 == content.len();
+            let item = self.inode(*id);
+            let (branch, indent) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            let suffix = if item.is_dir() { "/" } else { "" };
+            lines.push(format!(
+                "{}{}{}{} ({})",
+                prefix,
+                branch,
+                item.name(),
+                suffix,
+                item.size()
+            ));
+            if item.is_dir() {
+                self.render_tree_content(*id, &format!("{}{}", prefix, indent), lines);
+            }
+        }
+    }
+
+    pub fn render_du(&self) -> String {
+        let mut dirs: Vec<InodeId> = self
+            .flatten()
+            .into_iter()
+            .filter(|id| self.inode(*id).is_dir())
+            .collect();
+        dirs.sort_by_key(|id| std::cmp::Reverse(self.inode(*id).size()));
+        dirs.iter()
+            .map(|id| format!("{}\t{}", human_size(self.inode(*id).size()), self.path(*id)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(self.root(), 0, &mut json);
+        json
+    }
+
+    fn write_json(&self, id: InodeId, depth: usize, json: &mut String) {
+        let item = self.inode(id);
+        let indent = "  ".repeat(depth);
+        json.push_str(&format!(
+            "{}{{\"name\": {}, \"type\": \"{}\", \"size\": {}",
+            indent,
+            json_string(item.name()),
+            if item.is_dir() { "dir" } else { "file" },
+            item.size()
+        ));
+        if item.is_dir() {
+            let content = self.content(id);
+            if content.is_empty() {
+                json.push_str(", \"content\": []");
+            } else {
+                json.push_str(", \"content\": [\n");
+                for (idx, child) in content.iter().enumerate() {
+                    self.write_json(*child, depth + 1, json);
+                    json.push_str(if idx + 1 == content.len() {
+                        "\n"
+                    } else {
+                        ",\n"
+                    });
+                }
+                json.push_str(&format!("{}]", indent));
+            }
+        }
+        json.push('}');
+    }
+}
+
+fn human_size(size: usize) -> String {
+    let units = ["K", "M", "G", "T", "P"];
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut value = size as f64;
+    let mut unit = "";
+    for next in units {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+
+    if value < 10.0 {
+        format!("{:.1}{}", (value * 10.0).ceil() / 10.0, unit)
+    } else {
+        format!("{}{}", value.ceil(), unit)
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_example_fs1;
+
+    #[test]
+    fn test_render_tree() {
+        let expected = "\
+/ (48381165)
+├── a/ (94853)
+│   ├── e/ (584)
+│   │   └── i (584)
+│   ├── f (29116)
+│   ├── g (2557)
+│   └── h.lst (62596)
+├── b.txt (14848514)
+├── c.dat (8504156)
+└── d/ (24933642)
+    ├── j (4060174)
+    ├── d.log (8033020)
+    ├── d.ext (5626152)
+    └── k (7214296)";
+        assert_eq!(expected, create_example_fs1().render_tree());
+    }
+
+    #[test]
+    fn test_render_du() {
+        let expected = "47M\t/\n24M\t/d\n93K\t/a\n584\t/a/e";
+        assert_eq!(expected, create_example_fs1().render_du());
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!("0", human_size(0));
+        assert_eq!("1023", human_size(1023));
+        assert_eq!("1.0K", human_size(1024));
+        assert_eq!("1.1K", human_size(1025));
+        assert_eq!("10K", human_size(10 * 1024));
+        assert_eq!("3.0G", human_size(3 * 1024 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let mut fs = Filesystem::new();
+        let root = fs.root();
+        let dir = fs.add_dir(root, "a \"b\"").unwrap();
+        fs.add_file(dir, "c", 5).unwrap();
+        fs.add_dir(root, "empty").unwrap();
+        let expected = r#"{"name": "/", "type": "dir", "size": 5, "content": [
+  {"name": "a \"b\"", "type": "dir", "size": 5, "content": [
+    {"name": "c", "type": "file", "size": 5}
+  ]},
+  {"name": "empty", "type": "dir", "size": 0, "content": []}
+]}"#;
+        assert_eq!(expected, fs.to_json());
+    }
+}
@@ -6,6 +6,6 @@ fn main() {
     let result = process_part1(&input);
     match result {
         Ok(output) => println!("{}", output),
-        Err(_) => println!("Invalid input"),
+        Err(err) => println!("Invalid input: {}", err),
     }
 }
@@ -0,0 +1,36 @@
+use day08::{build_grid, heatmap_ppm, render_heatmap, render_visibility, visibility_ppm};
+use std::{env, fs, fs::read_to_string};
+
+const PIXELS_PER_TREE: usize = 4;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let path = args.get(2).map_or("./input/input.txt", |s| s.as_str());
+    let input = read_to_string(path).unwrap();
+    let grid = match build_grid(&input) {
+        Ok(grid) => grid,
+        Err(err) => {
+            println!("Invalid input: {}", err);
+            return;
+        }
+    };
+
+    let map = args.first().map(|s| s.as_str());
+    let output = args.get(1).map(|s| s.as_str());
+    match (map, output) {
+        (Some("visibility"), Some("ascii")) => println!("{}", render_visibility(&grid, false)),
+        (Some("visibility"), Some("ansi")) => println!("{}", render_visibility(&grid, true)),
+        (Some("heatmap"), Some("ascii")) => println!("{}", render_heatmap(&grid, false)),
+        (Some("heatmap"), Some("ansi")) => println!("{}", render_heatmap(&grid, true)),
+        (Some(map @ ("visibility" | "heatmap")), Some(file)) if file.ends_with(".ppm") => {
+            let ppm = if map == "visibility" {
+                visibility_ppm(&grid, PIXELS_PER_TREE)
+            } else {
+                heatmap_ppm(&grid, PIXELS_PER_TREE)
+            };
+            fs::write(file, ppm).unwrap();
+            println!("Wrote {}", file);
+        }
+        _ => println!("Usage: render <visibility|heatmap> <ascii|ansi|FILE.ppm> [input]"),
+    }
+}
@@ -0,0 +1,38 @@
+use day08::{build_grid, scenic_score_grid_with, visibility_grid_with, Directions};
+use std::{env, fs::read_to_string};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let directions = match args.first().map(|s| s.parse::<Directions>()) {
+        Some(Ok(directions)) => directions,
+        Some(Err(err)) => {
+            println!("{}", err);
+            return;
+        }
+        None => {
+            println!("Usage: sight <cardinal|diagonal|ROWS/COLUMNS,...> [input]");
+            return;
+        }
+    };
+    let path = args.get(1).map_or("./input/input.txt", |s| s.as_str());
+    let input = read_to_string(path).unwrap();
+    let grid = match build_grid(&input) {
+        Ok(grid) => grid,
+        Err(err) => {
+            println!("Invalid input: {}", err);
+            return;
+        }
+    };
+
+    let visible = visibility_grid_with(&grid, &directions)
+        .iter()
+        .filter(|visible| **visible)
+        .count();
+    let best = scenic_score_grid_with(&grid, &directions)
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0);
+    println!("Visible trees: {}", visible);
+    println!("Highest scenic score: {}", best);
+}
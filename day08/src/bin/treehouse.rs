@@ -0,0 +1,58 @@
+use day08::{build_grid, top_treehouses, Constraints};
+use std::{env, fs::read_to_string};
+
+const USAGE: &str = "Usage: treehouse [-k COUNT] [--visible] [--min-height HEIGHT] [input]";
+
+fn main() {
+    let Some((k, constraints, path)) = parse_args() else {
+        println!("{}", USAGE);
+        return;
+    };
+    let input = read_to_string(path).unwrap();
+    let grid = match build_grid(&input) {
+        Ok(grid) => grid,
+        Err(err) => {
+            println!("Invalid input: {}", err);
+            return;
+        }
+    };
+
+    for (rank, treehouse) in top_treehouses(&grid, k, &constraints).iter().enumerate() {
+        let distances = &treehouse.distances;
+        println!(
+            "#{} row {}, column {}: height {}, score {} (up {}, down {}, left {}, right {}){}",
+            rank + 1,
+            treehouse.coord.row + 1,
+            treehouse.coord.column + 1,
+            treehouse.height,
+            treehouse.score,
+            distances.up,
+            distances.down,
+            distances.left,
+            distances.right,
+            if treehouse.visible_from_edge {
+                ", visible from the edge"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
+fn parse_args() -> Option<(usize, Constraints, String)> {
+    let mut k = 5;
+    let mut constraints = Constraints::default();
+    let mut path = String::from("./input/input.txt");
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-k" => k = args.next()?.parse().ok()?,
+            "--visible" => constraints.visible_from_edge = true,
+            "--min-height" => constraints.min_height = args.next()?.parse().ok()?,
+            _ if arg.starts_with('-') => return None,
+            _ => path = arg,
+        }
+    }
+    Some((k, constraints, path))
+}
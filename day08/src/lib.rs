@@ -1,4 +1,15 @@
-use array2d::{Array2D, Error};
+use grid::{Coord, Grid, GridError};
+use std::fmt;
+
+mod render;
+mod sight;
+mod treehouse;
+
+pub use render::{heatmap_ppm, render_heatmap, render_visibility, visibility_ppm};
+pub use sight::{
+    ray, scenic_score_grid_with, viewing_distance, visibility_grid_with, Directions, Slope,
+};
+pub use treehouse::{top_treehouses, Constraints, Treehouse, ViewingDistances};
 
 pub fn process_part1(input: &str) -> Result<usize, Error> {
     let grid = build_grid(input)?;
@@ -10,93 +21,114 @@ pub fn process_part2(input: &str) -> Result<usize, Error> {
     Ok(highest_scenic_score(&grid))
 }
 
-fn build_grid(input: &str) -> Result<Array2D<u32>, Error> {
-    let rows: Vec<_> = input.lines().map(|line| build_row(line)).collect();
-    Array2D::from_rows(&rows)
-}
-
-fn build_row(line: &str) -> Vec<u32> {
-    line.chars()
-        .filter_map(|c| c.to_string().parse::<u32>().ok())
-        .collect()
-}
-
-fn count_visible(grid: &Array2D<u32>) -> usize {
-    grid.indices_row_major()
-        .filter(|pair| is_visible(grid, pair.0, pair.1))
-        .count()
+#[derive(PartialEq, Eq, Debug)]
+pub enum Error {
+    Grid(GridError),
+    InvalidDirections(String),
 }
 
-fn highest_scenic_score(grid: &Array2D<u32>) -> usize {
-    grid.indices_row_major()
-        .map(|pair| scenic_score(grid, pair.0, pair.1))
-        .max()
-        .unwrap_or(0)
-}
-
-fn is_visible(grid: &Array2D<u32>, row_idx: usize, column_idx: usize) -> bool {
-    match (grid.row_iter(row_idx), grid.column_iter(column_idx)) {
-        (Ok(row_iter), Ok(column_iter)) => {
-            let row: Vec<u32> = row_iter.copied().collect();
-            let column: Vec<u32> = column_iter.copied().collect();
-            is_visible_from_top(&column, row_idx)
-                || is_visible_from_bottom(&column, row_idx)
-                || is_visible_from_left(&row, column_idx)
-                || is_visible_from_right(&row, column_idx)
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Grid(err) => write!(f, "{}", err),
+            Error::InvalidDirections(directions) => write!(
+                f,
+                "unknown directions \"{}\", expected cardinal, diagonal or slopes like 1/2,2/1",
+                directions
+            ),
         }
-        _ => false,
     }
 }
 
-fn scenic_score(grid: &Array2D<u32>, row_idx: usize, column_idx: usize) -> usize {
-    match (grid.row_iter(row_idx), grid.column_iter(column_idx)) {
-        (Ok(row_iter), Ok(column_iter)) => {
-            let row: Vec<u32> = row_iter.copied().collect();
-            let column: Vec<u32> = column_iter.copied().collect();
-            scenic_score_top_left(&column, row_idx)
-                * scenic_score_bottom_right(&column, row_idx)
-                * scenic_score_top_left(&row, column_idx)
-                * scenic_score_bottom_right(&row, column_idx)
-        }
-        _ => 0,
-    }
+pub fn build_grid(input: &str) -> Result<Grid<u32>, Error> {
+    Grid::parse(input, |c| c.to_digit(10)).map_err(Error::Grid)
 }
 
-fn is_visible_from_top(column: &[u32], row_idx: usize) -> bool {
-    is_greater_than_rest(column[row_idx], &column[0..row_idx])
+fn count_visible(grid: &Grid<u32>) -> usize {
+    visibility_grid(grid)
+        .iter()
+        .filter(|visible| **visible)
+        .count()
 }
 
-fn is_visible_from_bottom(column: &[u32], row_idx: usize) -> bool {
-    is_greater_than_rest(column[row_idx], &column[row_idx + 1..column.len()])
+fn highest_scenic_score(grid: &Grid<u32>) -> usize {
+    scenic_score_grid(grid).iter().copied().max().unwrap_or(0)
 }
 
-fn is_visible_from_left(row: &[u32], column_idx: usize) -> bool {
-    is_greater_than_rest(row[column_idx], &row[0..column_idx])
+pub fn visibility_grid(grid: &Grid<u32>) -> Grid<bool> {
+    let mut visible = Grid::new(grid.width(), grid.height(), false);
+    for_each_line_of_sight(grid, |cells, heights| {
+        for (cell, seen) in cells.iter().zip(visible_from_start(heights)) {
+            if seen {
+                visible[*cell] = true;
+            }
+        }
+    });
+    visible
 }
 
-fn is_visible_from_right(row: &[u32], column_idx: usize) -> bool {
-    is_greater_than_rest(row[column_idx], &row[column_idx + 1..row.len()])
+pub fn scenic_score_grid(grid: &Grid<u32>) -> Grid<usize> {
+    let mut scores = Grid::new(grid.width(), grid.height(), 1);
+    for_each_line_of_sight(grid, |cells, heights| {
+        for (cell, distance) in cells.iter().zip(viewing_distances(heights)) {
+            scores[*cell] *= distance;
+        }
+    });
+    scores
 }
 
-fn is_greater_than_rest(value: u32, rest: &[u32]) -> bool {
-    rest.iter().find(|v| **v >= value).is_none()
+// every row and column, read from both ends
+fn for_each_line_of_sight<F>(grid: &Grid<u32>, mut f: F)
+where
+    F: FnMut(&[Coord], &[u32]),
+{
+    let (height, width) = (grid.height(), grid.width());
+    let rows = (0..height).map(|row| {
+        (0..width)
+            .map(|column| Coord::new(row, column))
+            .collect::<Vec<_>>()
+    });
+    let columns = (0..width).map(|column| {
+        (0..height)
+            .map(|row| Coord::new(row, column))
+            .collect::<Vec<_>>()
+    });
+    for mut cells in rows.chain(columns) {
+        for _ in 0..2 {
+            let heights: Vec<u32> = cells.iter().map(|cell| grid[*cell]).collect();
+            f(&cells, &heights);
+            cells.reverse();
+        }
+    }
 }
 
-fn scenic_score_top_left(block: &[u32], idx: usize) -> usize {
-    block[0..idx]
+fn visible_from_start(heights: &[u32]) -> Vec<bool> {
+    let mut tallest = None;
+    heights
         .iter()
-        .rev()
-        .enumerate()
-        .find(|pair| *pair.1 >= block[idx])
-        .map_or(idx, |pair| pair.0 + 1)
+        .map(|height| {
+            let visible = Some(*height) > tallest;
+            tallest = tallest.max(Some(*height));
+            visible
+        })
+        .collect()
 }
 
-fn scenic_score_bottom_right(block: &[u32], idx: usize) -> usize {
-    block[idx + 1..block.len()]
+// trees shorter than the current one can never block a later tree again
+fn viewing_distances(heights: &[u32]) -> Vec<usize> {
+    let mut blockers: Vec<usize> = Vec::new();
+    heights
         .iter()
         .enumerate()
-        .find(|pair| *pair.1 >= block[idx])
-        .map_or(block.len() - 1 - idx, |pair| pair.0 + 1)
+        .map(|(idx, height)| {
+            while blockers.last().is_some_and(|top| heights[*top] < *height) {
+                blockers.pop();
+            }
+            let distance = blockers.last().map_or(idx, |top| idx - top);
+            blockers.push(idx);
+            distance
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -105,129 +137,142 @@ mod tests {
 
     #[test]
     fn test_count_visible() {
-        let grid: Array2D<u32> = Array2D::from_rows(&vec![
-            vec![3, 0, 3, 7, 3],
-            vec![2, 5, 5, 1, 2],
-            vec![6, 5, 3, 3, 2],
-            vec![3, 3, 5, 4, 9],
-            vec![3, 5, 3, 9, 0],
-        ])
-        .unwrap();
+        let grid = example_grid();
 
         assert_eq!(21, count_visible(&grid))
     }
 
     #[test]
     fn test_highest_scenic_score() {
-        let grid: Array2D<u32> = Array2D::from_rows(&vec![
-            vec![3, 0, 3, 7, 3],
-            vec![2, 5, 5, 1, 2],
-            vec![6, 5, 3, 3, 2],
-            vec![3, 3, 5, 4, 9],
-            vec![3, 5, 3, 9, 0],
-        ])
-        .unwrap();
+        let grid = example_grid();
 
         assert_eq!(8, highest_scenic_score(&grid))
     }
 
-    #[test]
-    fn test_is_visible() {
-        let grid: Array2D<u32> = Array2D::from_rows(&vec![
+    fn example_grid() -> Grid<u32> {
+        Grid::from_rows(vec![
             vec![3, 0, 3, 7, 3],
             vec![2, 5, 5, 1, 2],
             vec![6, 5, 3, 3, 2],
             vec![3, 3, 5, 4, 9],
             vec![3, 5, 3, 9, 0],
         ])
-        .unwrap();
-
-        assert!(is_visible(&grid, 2, 3));
-        assert!(is_visible(&grid, 1, 1));
-        assert!(is_visible(&grid, 4, 3));
-
-        assert!(!is_visible(&grid, 1, 3));
-        assert!(!is_visible(&grid, 2, 2));
-        assert!(!is_visible(&grid, 3, 1));
-        assert!(!is_visible(&grid, 3, 3));
+        .unwrap()
     }
 
     #[test]
-    fn test_scenic_score() {
-        let grid: Array2D<u32> = Array2D::from_rows(&vec![
-            vec![3, 0, 3, 7, 3],
-            vec![2, 5, 5, 1, 2],
-            vec![6, 5, 3, 3, 2],
-            vec![3, 3, 5, 4, 9],
-            vec![3, 5, 3, 9, 0],
-        ])
-        .unwrap();
-
-        assert_eq!(8, scenic_score(&grid, 3, 2));
-        assert_eq!(4, scenic_score(&grid, 1, 2))
+    fn test_build_grid() {
+        let grid = build_grid("30373\n25512\n65332\n33549\n35390\n").unwrap();
+        assert_eq!(example_grid(), grid);
+
+        assert_eq!(Err(Error::Grid(GridError::Empty)), build_grid(""));
+        assert_eq!(Err(Error::Grid(GridError::Empty)), build_grid("\n\n"));
+        assert_eq!(
+            Err(Error::Grid(GridError::RaggedRow {
+                row: 2,
+                expected: 3,
+                found: 2
+            })),
+            build_grid("123\n456\n78\n")
+        );
+        assert_eq!(
+            Err(Error::Grid(GridError::InvalidCharacter {
+                row: 1,
+                column: 1,
+                found: 'x'
+            })),
+            build_grid("123\n4x6\n789\n")
+        );
     }
 
     #[test]
-    fn test_is_visible_from_top() {
-        let column = &vec![7, 1, 3, 4, 9];
-        assert!(is_visible_from_top(column, 0));
-        assert!(is_visible_from_top(column, 4));
-        assert!(!is_visible_from_top(column, 1));
-    }
+    fn test_visibility_grid() {
+        let visible = visibility_grid(&example_grid());
 
-    #[test]
-    fn test_is_visible_from_left() {
-        let row = &vec![2, 5, 5, 1, 2];
-        assert!(is_visible_from_left(row, 0));
-        assert!(is_visible_from_left(row, 1));
-        assert!(!is_visible_from_left(row, 2));
-        assert!(!is_visible_from_left(row, 3));
+        assert!(visible[Coord::new(2, 3)]);
+        assert!(visible[Coord::new(1, 1)]);
+        assert!(visible[Coord::new(4, 3)]);
+
+        assert!(!visible[Coord::new(1, 3)]);
+        assert!(!visible[Coord::new(2, 2)]);
+        assert!(!visible[Coord::new(3, 1)]);
+        assert!(!visible[Coord::new(3, 3)]);
     }
 
     #[test]
-    fn test_is_visible_from_right() {
-        let row = &vec![2, 5, 5, 1, 2];
-        assert!(is_visible_from_right(row, 4));
-        assert!(!is_visible_from_right(row, 3));
-        assert!(is_visible_from_right(row, 2));
-        assert!(!is_visible_from_right(row, 1));
+    fn test_scenic_score_grid() {
+        let scores = scenic_score_grid(&example_grid());
+
+        assert_eq!(8, scores[Coord::new(3, 2)]);
+        assert_eq!(4, scores[Coord::new(1, 2)]);
+        assert_eq!(0, scores[Coord::new(0, 2)]);
+        assert_eq!(0, scores[Coord::new(2, 4)]);
     }
 
     #[test]
-    fn test_scenic_score_top_left() {
+    fn test_visible_from_start() {
         // top
-        let column = &vec![3, 5, 3, 5, 3];
-        assert_eq!(0, scenic_score_top_left(column, 0));
-        assert_eq!(1, scenic_score_top_left(column, 1));
-        assert_eq!(1, scenic_score_top_left(column, 2));
-        assert_eq!(2, scenic_score_top_left(column, 3));
-        assert_eq!(1, scenic_score_top_left(column, 4));
+        let column = &[7, 1, 3, 4, 9];
+        assert_eq!(
+            vec![true, false, false, false, true],
+            visible_from_start(column)
+        );
 
         // left
-        let row = &vec![2, 5, 5, 1, 2];
-        assert_eq!(0, scenic_score_top_left(row, 0));
-        assert_eq!(1, scenic_score_top_left(row, 1));
-        assert_eq!(1, scenic_score_top_left(row, 2));
-        assert_eq!(1, scenic_score_top_left(row, 3));
-        assert_eq!(2, scenic_score_top_left(row, 4));
+        let row = &[2, 5, 5, 1, 2];
+        assert_eq!(
+            vec![true, true, false, false, false],
+            visible_from_start(row)
+        );
+
+        // right
+        let row = &[2, 1, 5, 5, 2];
+        assert_eq!(
+            vec![true, false, true, false, false],
+            visible_from_start(row)
+        );
     }
 
     #[test]
-    fn test_scenic_score_bottom_right() {
-        // bottom
-        let column = &vec![3, 5, 3, 5, 3];
-        assert_eq!(1, scenic_score_bottom_right(column, 0));
-        assert_eq!(2, scenic_score_bottom_right(column, 1));
-        assert_eq!(1, scenic_score_bottom_right(column, 2));
-        assert_eq!(1, scenic_score_bottom_right(column, 3));
-        assert_eq!(0, scenic_score_bottom_right(column, 4));
+    fn test_viewing_distances() {
+        let column = &[3, 5, 3, 5, 3];
+        assert_eq!(vec![0, 1, 1, 2, 1], viewing_distances(column));
 
-        // right
-        let row = &vec![2, 5, 5, 1, 2];
-        assert_eq!(1, scenic_score_bottom_right(row, 0));
-        assert_eq!(1, scenic_score_bottom_right(row, 1));
-        assert_eq!(2, scenic_score_bottom_right(row, 2));
-        assert_eq!(1, scenic_score_bottom_right(row, 3));
-        assert_eq!(0, scenic_score_bottom_right(row, 4));
+        let row = &[2, 5, 5, 1, 2];
+        assert_eq!(vec![0, 1, 1, 1, 2], viewing_distances(row));
+
+        let row = &[2, 1, 5, 5, 2];
+        assert_eq!(vec![0, 1, 2, 1, 1], viewing_distances(row));
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_large_forest() {
+        let grid = random_forest(5000, 5000, 7);
+
+        let start = std::time::Instant::now();
+        let visible = count_visible(&grid);
+        println!("visibility: {:?} ({} visible)", start.elapsed(), visible);
+
+        let start = std::time::Instant::now();
+        let best = highest_scenic_score(&grid);
+        println!("scenic score: {:?} (best {})", start.elapsed(), best);
+    }
+
+    fn random_forest(num_rows: usize, num_columns: usize, seed: u64) -> Grid<u32> {
+        let mut state = seed;
+        let heights: Vec<Vec<u32>> = (0..num_rows)
+            .map(|_| {
+                (0..num_columns)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        ((state >> 33) % 10) as u32
+                    })
+                    .collect()
+            })
+            .collect();
+        Grid::from_rows(heights).unwrap()
     }
 }
@@ -0,0 +1,148 @@
+use crate::{scenic_score_grid, visibility_grid};
+use grid::{Coord, Grid};
+
+// from the lowest to the highest score
+const RAMP: &[u8] = b" .:-=+*#%";
+const BEST: char = '@';
+const RESET: &str = "\x1b[0m";
+
+pub fn render_visibility(grid: &Grid<u32>, ansi: bool) -> String {
+    let visible = visibility_grid(grid);
+    let best = best_position(&scenic_score_grid(grid));
+    grid.render(|cell, _| {
+        let height = char::from_digit(grid[cell], 10).unwrap_or('?');
+        match (ansi, Some(cell) == best, visible[cell]) {
+            (false, true, _) => BEST.to_string(),
+            (false, false, true) => height.to_string(),
+            (false, false, false) => String::from("."),
+            (true, true, _) => format!("\x1b[1;30;43m{}{}", height, RESET),
+            (true, false, true) => format!("\x1b[1;32m{}{}", height, RESET),
+            (true, false, false) => format!("\x1b[2m{}{}", height, RESET),
+        }
+    })
+}
+
+pub fn render_heatmap(grid: &Grid<u32>, ansi: bool) -> String {
+    let scores = scenic_score_grid(grid);
+    let best = best_position(&scores);
+    let max = best.map_or(0, |cell| scores[cell]);
+    grid.render(|cell, _| {
+        let intensity = intensity(scores[cell], max);
+        match (ansi, Some(cell) == best) {
+            (false, true) => BEST.to_string(),
+            (false, false) => {
+                let level = (intensity * (RAMP.len() - 1) as f64).round() as usize;
+                char::from(RAMP[level]).to_string()
+            }
+            (true, true) => format!("\x1b[1;30;47m{}{}{}", BEST, BEST, RESET),
+            (true, false) => {
+                let [r, g, b] = heat_color(intensity);
+                format!("\x1b[48;2;{};{};{}m  {}", r, g, b, RESET)
+            }
+        }
+    })
+}
+
+pub fn visibility_ppm(grid: &Grid<u32>, scale: usize) -> Vec<u8> {
+    let visible = visibility_grid(grid);
+    let best = best_position(&scenic_score_grid(grid));
+    grid.to_ppm(scale, |cell, _| {
+        let shade = 80 + grid[cell].min(9) as u8 * 16;
+        if Some(cell) == best {
+            [230, 30, 30]
+        } else if visible[cell] {
+            [0, shade, 0]
+        } else {
+            [shade / 2, shade / 2, shade / 2]
+        }
+    })
+}
+
+pub fn heatmap_ppm(grid: &Grid<u32>, scale: usize) -> Vec<u8> {
+    let scores = scenic_score_grid(grid);
+    let best = best_position(&scores);
+    let max = best.map_or(0, |cell| scores[cell]);
+    grid.to_ppm(scale, |cell, _| {
+        if Some(cell) == best {
+            [255, 255, 255]
+        } else {
+            heat_color(intensity(scores[cell], max))
+        }
+    })
+}
+
+fn best_position(scores: &Grid<usize>) -> Option<Coord> {
+    scores
+        .coords()
+        .max_by(|a, b| scores[*a].cmp(&scores[*b]).then(b.cmp(a)))
+}
+
+// logarithmic, since a few trees score orders of magnitude above the rest
+fn intensity(score: usize, max: usize) -> f64 {
+    if max == 0 {
+        0.0
+    } else {
+        (score as f64).ln_1p() / (max as f64).ln_1p()
+    }
+}
+
+// black → purple → red → yellow
+fn heat_color(intensity: f64) -> [u8; 3] {
+    const STOPS: [[f64; 3]; 4] = [
+        [0.0, 0.0, 0.0],
+        [80.0, 0.0, 120.0],
+        [220.0, 40.0, 40.0],
+        [255.0, 220.0, 0.0],
+    ];
+    let position = intensity.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let idx = (position.floor() as usize).min(STOPS.len() - 2);
+    let t = position - idx as f64;
+    let (from, to) = (STOPS[idx], STOPS[idx + 1]);
+    [0, 1, 2].map(|channel| (from[channel] + (to[channel] - from[channel]) * t).round() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_grid;
+
+    const EXAMPLE: &str = "30373\n25512\n65332\n33549\n35390\n";
+
+    #[test]
+    fn test_render_visibility() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        assert_eq!(
+            "30373\n255.2\n65.32\n3.@.9\n35390",
+            render_visibility(&grid, false)
+        );
+    }
+
+    #[test]
+    fn test_render_heatmap() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        assert_eq!(
+            "     \n -*- \n #-= \n -@+ \n     ",
+            render_heatmap(&grid, false)
+        );
+    }
+
+    #[test]
+    fn test_best_position_prefers_first() {
+        let scores = Grid::from_rows(vec![vec![0, 3, 1], vec![3, 2, 0]]).unwrap();
+        assert_eq!(Some(Coord::new(0, 1)), best_position(&scores));
+    }
+
+    #[test]
+    fn test_to_ppm() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        let ppm = heatmap_ppm(&grid, 3);
+        let header = b"P6\n15 15\n255\n";
+
+        assert_eq!(header, &ppm[..header.len()]);
+        assert_eq!(header.len() + 15 * 15 * 3, ppm.len());
+        // the best tree at row 3, column 2 covers pixels 9..12 × 6..9
+        let pixel = header.len() + (10 * 15 + 7) * 3;
+        assert_eq!([255, 255, 255], ppm[pixel..pixel + 3]);
+        assert_eq!([0, 0, 0], ppm[header.len()..header.len() + 3]);
+    }
+}
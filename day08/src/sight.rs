@@ -0,0 +1,246 @@
+use crate::{scenic_score_grid, visibility_grid, Error};
+use grid::{Coord, Grid, Offset};
+use std::str::FromStr;
+
+// rows and columns per step, reduced to have no common divisor
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Slope {
+    rows: i32,
+    columns: i32,
+}
+
+impl Slope {
+    pub fn new(rows: i32, columns: i32) -> Option<Slope> {
+        let divisor = gcd(rows.unsigned_abs(), columns.unsigned_abs()) as i32;
+        if divisor == 0 {
+            return None;
+        }
+        Some(Slope {
+            rows: rows / divisor,
+            columns: columns / divisor,
+        })
+    }
+
+    fn opposite(self) -> Slope {
+        Slope {
+            rows: -self.rows,
+            columns: -self.columns,
+        }
+    }
+}
+
+// every line is followed both ways, so a slope and its opposite are one line
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Directions {
+    Cardinal,
+    WithDiagonals,
+    Slopes(Vec<Slope>),
+}
+
+impl Directions {
+    fn rays(&self) -> Vec<Slope> {
+        let lines = match self {
+            Directions::Cardinal => vec![Slope::new(0, 1), Slope::new(1, 0)],
+            Directions::WithDiagonals => vec![
+                Slope::new(0, 1),
+                Slope::new(1, 0),
+                Slope::new(1, 1),
+                Slope::new(1, -1),
+            ],
+            Directions::Slopes(slopes) => slopes.iter().copied().map(Some).collect(),
+        };
+        let mut rays = Vec::new();
+        for slope in lines.into_iter().flatten() {
+            if !rays.contains(&slope) {
+                rays.extend([slope, slope.opposite()]);
+            }
+        }
+        rays
+    }
+}
+
+// `cardinal`, `diagonal` or slopes written as `rows/columns`, e.g. `1/2,2/1`
+impl FromStr for Directions {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cardinal" => return Ok(Directions::Cardinal),
+            "diagonal" => return Ok(Directions::WithDiagonals),
+            _ => {}
+        }
+        s.split(',')
+            .map(|slope| {
+                slope
+                    .split_once('/')
+                    .and_then(|(rows, columns)| Some((rows.parse().ok()?, columns.parse().ok()?)))
+                    .and_then(|(rows, columns)| Slope::new(rows, columns))
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Directions::Slopes)
+            .ok_or_else(|| Error::InvalidDirections(s.to_string()))
+    }
+}
+
+// one cell per step along the major axis, rounding the other like Bresenham
+pub fn ray(grid: &Grid<u32>, origin: Coord, slope: Slope) -> impl Iterator<Item = Coord> + '_ {
+    let (rows, columns) = (slope.rows as isize, slope.columns as isize);
+    let major = rows.abs().max(columns.abs());
+    (1..)
+        .map(move |step| {
+            origin.offset(Offset::new(
+                div_round(step * rows, major),
+                div_round(step * columns, major),
+            ))
+        })
+        .take_while(|cell| cell.is_some_and(|cell| grid.contains(cell)))
+        .flatten()
+}
+
+pub fn viewing_distance(grid: &Grid<u32>, cell: Coord, slope: Slope) -> usize {
+    let height = grid[cell];
+    let mut distance = 0;
+    for other in ray(grid, cell, slope) {
+        distance += 1;
+        if grid[other] >= height {
+            break;
+        }
+    }
+    distance
+}
+
+pub fn visibility_grid_with(grid: &Grid<u32>, directions: &Directions) -> Grid<bool> {
+    if *directions == Directions::Cardinal {
+        return visibility_grid(grid);
+    }
+    let rays = directions.rays();
+    let mut visible = Grid::new(grid.width(), grid.height(), false);
+    for cell in grid.coords() {
+        visible[cell] = rays
+            .iter()
+            .any(|slope| ray(grid, cell, *slope).all(|other| grid[other] < grid[cell]));
+    }
+    visible
+}
+
+pub fn scenic_score_grid_with(grid: &Grid<u32>, directions: &Directions) -> Grid<usize> {
+    if *directions == Directions::Cardinal {
+        return scenic_score_grid(grid);
+    }
+    let rays = directions.rays();
+    let mut scores = Grid::new(grid.width(), grid.height(), 0);
+    for cell in grid.coords() {
+        scores[cell] = rays
+            .iter()
+            .map(|slope| viewing_distance(grid, cell, *slope))
+            .product();
+    }
+    scores
+}
+
+// rounds halves towards zero so that opposite rays mirror each other
+fn div_round(numerator: isize, denominator: isize) -> isize {
+    let rounded = (2 * numerator.abs() + denominator - 1) / (2 * denominator);
+    rounded * numerator.signum()
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_grid;
+
+    const EXAMPLE: &str = "30373\n25512\n65332\n33549\n35390\n";
+
+    fn count(visible: &Grid<bool>) -> usize {
+        visible.iter().filter(|visible| **visible).count()
+    }
+
+    #[test]
+    fn test_parse_directions() {
+        assert_eq!(Ok(Directions::Cardinal), "cardinal".parse());
+        assert_eq!(Ok(Directions::WithDiagonals), "diagonal".parse());
+        assert_eq!(
+            Ok(Directions::Slopes(vec![
+                Slope::new(1, 2).unwrap(),
+                Slope::new(-3, 1).unwrap()
+            ])),
+            "2/4,-3/1".parse()
+        );
+        assert_eq!(
+            Err(Error::InvalidDirections(String::from("0/0"))),
+            "0/0".parse::<Directions>()
+        );
+        assert!("up".parse::<Directions>().is_err());
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        let cells = |(row, column), rows, columns| -> Vec<(usize, usize)> {
+            let origin = Coord::new(row, column);
+            ray(&grid, origin, Slope::new(rows, columns).unwrap())
+                .map(|cell| (cell.row, cell.column))
+                .collect()
+        };
+
+        assert_eq!(vec![(2, 1), (2, 0)], cells((2, 2), 0, -1));
+        assert_eq!(vec![(1, 3), (0, 4)], cells((2, 2), -1, 1));
+        assert_eq!(vec![(0, 1), (1, 2), (1, 3), (2, 4)], cells((0, 0), 1, 2));
+        assert_eq!(vec![(4, 3), (3, 2), (3, 1), (2, 0)], cells((4, 4), -1, -2));
+        assert!(cells((0, 0), -1, 0).is_empty());
+    }
+
+    #[test]
+    fn test_cardinal_rays_match_sweeps() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        let cardinal =
+            Directions::Slopes(vec![Slope::new(0, 1).unwrap(), Slope::new(1, 0).unwrap()]);
+
+        assert_eq!(
+            visibility_grid(&grid),
+            visibility_grid_with(&grid, &cardinal)
+        );
+        assert_eq!(
+            scenic_score_grid(&grid),
+            scenic_score_grid_with(&grid, &cardinal)
+        );
+    }
+
+    #[test]
+    fn test_repeated_lines() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        let single: Directions = "1/0".parse().unwrap();
+        for repeated in ["1/0,-1/0", "1/0,2/0", "1/0,1/0"] {
+            let repeated: Directions = repeated.parse().unwrap();
+            assert_eq!(single.rays(), repeated.rays());
+            assert_eq!(
+                scenic_score_grid_with(&grid, &single),
+                scenic_score_grid_with(&grid, &repeated)
+            );
+            assert_eq!(
+                visibility_grid_with(&grid, &single),
+                visibility_grid_with(&grid, &repeated)
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_diagonals() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        let visible = visibility_grid_with(&grid, &Directions::WithDiagonals);
+        let scores = scenic_score_grid_with(&grid, &Directions::WithDiagonals);
+
+        assert_eq!(22, count(&visible));
+        assert!(visible[Coord::new(3, 3)]);
+        assert!(!visible[Coord::new(2, 2)]);
+        assert_eq!(16, scores[Coord::new(3, 2)]);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{scenic_score_grid, viewing_distance, visibility_grid, Slope};
+use grid::{Coord, Grid};
+use std::cmp::{Ordering, Reverse};
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Constraints {
+    pub visible_from_edge: bool,
+    pub min_height: u32,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct ViewingDistances {
+    pub up: usize,
+    pub down: usize,
+    pub left: usize,
+    pub right: usize,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct Treehouse {
+    pub coord: Coord,
+    pub height: u32,
+    pub score: usize,
+    pub distances: ViewingDistances,
+    pub visible_from_edge: bool,
+}
+
+// best first, ties broken in row-major order
+pub fn top_treehouses(grid: &Grid<u32>, k: usize, constraints: &Constraints) -> Vec<Treehouse> {
+    let scores = scenic_score_grid(grid);
+    let visible = visibility_grid(grid);
+    let mut candidates: Vec<Coord> = grid
+        .coords()
+        .filter(|cell| grid[*cell] >= constraints.min_height)
+        .filter(|cell| visible[*cell] || !constraints.visible_from_edge)
+        .collect();
+
+    let best_first = |a: &Coord, b: &Coord| -> Ordering {
+        (Reverse(scores[*a]), *a).cmp(&(Reverse(scores[*b]), *b))
+    };
+    if k < candidates.len() {
+        candidates.select_nth_unstable_by(k, best_first);
+        candidates.truncate(k);
+    }
+    candidates.sort_unstable_by(best_first);
+
+    candidates
+        .into_iter()
+        .map(|cell| Treehouse {
+            coord: cell,
+            height: grid[cell],
+            score: scores[cell],
+            distances: viewing_distances(grid, cell),
+            visible_from_edge: visible[cell],
+        })
+        .collect()
+}
+
+fn viewing_distances(grid: &Grid<u32>, cell: Coord) -> ViewingDistances {
+    let distance = |rows, columns| {
+        let slope = Slope::new(rows, columns).expect("cardinal slopes are never zero");
+        viewing_distance(grid, cell, slope)
+    };
+    ViewingDistances {
+        up: distance(-1, 0),
+        down: distance(1, 0),
+        left: distance(0, -1),
+        right: distance(0, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_grid;
+
+    const EXAMPLE: &str = "30373\n25512\n65332\n33549\n35390\n";
+
+    fn coords(treehouses: &[Treehouse]) -> Vec<(usize, usize)> {
+        treehouses
+            .iter()
+            .map(|treehouse| (treehouse.coord.row, treehouse.coord.column))
+            .collect()
+    }
+
+    #[test]
+    fn test_top_treehouses() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        let top = top_treehouses(&grid, 3, &Constraints::default());
+
+        assert_eq!(vec![(3, 2), (2, 1), (1, 2)], coords(&top));
+        assert_eq!(
+            Treehouse {
+                coord: Coord::new(3, 2),
+                height: 5,
+                score: 8,
+                distances: ViewingDistances {
+                    up: 2,
+                    down: 1,
+                    left: 2,
+                    right: 2
+                },
+                visible_from_edge: true,
+            },
+            top[0]
+        );
+    }
+
+    #[test]
+    fn test_top_treehouses_with_constraints() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        let unconstrained = top_treehouses(&grid, 4, &Constraints::default());
+        let visible = Constraints {
+            visible_from_edge: true,
+            ..Constraints::default()
+        };
+        let tall = Constraints {
+            min_height: 5,
+            ..Constraints::default()
+        };
+
+        assert_eq!(vec![(3, 2), (2, 1), (1, 2), (3, 3)], coords(&unconstrained));
+        assert_eq!(
+            vec![(3, 2), (2, 1), (1, 2), (2, 3)],
+            coords(&top_treehouses(&grid, 4, &visible))
+        );
+        assert_eq!(
+            vec![(3, 2), (2, 1)],
+            coords(&top_treehouses(&grid, 2, &tall))
+        );
+    }
+
+    #[test]
+    fn test_top_treehouses_ties_and_small_grids() {
+        let grid = build_grid(EXAMPLE).unwrap();
+        let all = top_treehouses(&grid, 100, &Constraints::default());
+        assert_eq!(25, all.len());
+        // the edge trees all score zero and come last in row-major order
+        assert_eq!((0, 0), coords(&all)[9]);
+        assert_eq!((4, 4), coords(&all)[24]);
+
+        assert!(top_treehouses(&grid, 0, &Constraints::default()).is_empty());
+    }
+}
@@ -3,6 +3,8 @@ use std::fs::read_to_string;
 
 fn main() {
   let input = read_to_string("./input/input.txt").unwrap();
-  let output = process_part2(&input);
-  println!("{}", output);  
-}
\ No newline at end of file
+  match process_part2(&input) {
+    Ok(output) => println!("{}", output),
+    Err(err) => println!("Invalid input: {}", err),
+  }
+}
@@ -0,0 +1,40 @@
+use day09::{render_rope, replay, trail_image, RopeConfig};
+use std::{env, fs, fs::read_to_string, thread, time::Duration};
+
+const FRAME_DELAY: Duration = Duration::from_millis(50);
+const PIXELS_PER_CELL: usize = 4;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let length = args.get(1).map_or(Ok(10), |arg| arg.parse::<usize>());
+    let reach = args.get(2).map_or(Ok(1), |arg| arg.parse::<usize>());
+    let config = match (length, reach) {
+        (Ok(length), Ok(reach)) => RopeConfig::new(length).with_reach(reach),
+        _ => return usage(),
+    };
+    let path = args.get(3).map_or("./input/input.txt", |s| s.as_str());
+    let input = read_to_string(path).unwrap();
+
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("final") => render_rope(&input, config).map(|drawing| println!("{}", drawing)),
+        Some("replay") => replay(&input, config, |frame| {
+            // clear the terminal and move the cursor home before every frame
+            println!("\x1b[2J\x1b[H{}", frame);
+            thread::sleep(FRAME_DELAY);
+        }),
+        Some(file) if file.ends_with(".ppm") => {
+            trail_image(&input, config, PIXELS_PER_CELL).map(|image| {
+                fs::write(file, image).unwrap();
+                println!("Wrote {}", file);
+            })
+        }
+        _ => return usage(),
+    };
+    if let Err(err) = result {
+        println!("Invalid input: {}", err);
+    }
+}
+
+fn usage() {
+    println!("Usage: render <final|replay|FILE.ppm> [rope length] [reach] [input]");
+}
@@ -0,0 +1,34 @@
+use day09::{rope_stats, RopeConfig};
+use std::{env, fs::read_to_string};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let length = args.first().map_or(Ok(10), |arg| arg.parse::<usize>());
+    let reach = args.get(1).map_or(Ok(1), |arg| arg.parse::<usize>());
+    let config = match (length, reach) {
+        (Ok(length), Ok(reach)) => RopeConfig::new(length).with_reach(reach),
+        _ => {
+            println!("Usage: stats [rope length] [reach] [input]");
+            return;
+        }
+    };
+    let path = args.get(2).map_or("./input/input.txt", |s| s.as_str());
+    let input = read_to_string(path).unwrap();
+
+    let stats = match rope_stats(&input, config) {
+        Ok(stats) => stats,
+        Err(err) => {
+            println!("Invalid input: {}", err);
+            return;
+        }
+    };
+    println!("knot  distinct  x range        y range");
+    for (idx, knot) in stats.knots.iter().enumerate() {
+        let bounds = &knot.bounds;
+        println!(
+            "{:>4}  {:>8}  {:>5}..{:<5}  {:>5}..{:<5}",
+            idx, knot.distinct_posns, bounds.min_x, bounds.max_x, bounds.min_y, bounds.max_y
+        );
+    }
+    println!("max head-tail distance: {}", stats.max_head_tail_distance);
+}
@@ -0,0 +1,22 @@
+use day09::{count_tail_voxels, RopeConfig};
+use std::{env, fs::read_to_string};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let length = args.first().map_or(Ok(10), |arg| arg.parse::<usize>());
+    let reach = args.get(1).map_or(Ok(1), |arg| arg.parse::<usize>());
+    let config = match (length, reach) {
+        (Ok(length), Ok(reach)) => RopeConfig::new(length).with_reach(reach),
+        _ => {
+            println!("Usage: voxels [rope length] [reach] [input]");
+            return;
+        }
+    };
+    let path = args.get(2).map_or("./input/input.txt", |s| s.as_str());
+    let input = read_to_string(path).unwrap();
+
+    match count_tail_voxels(&input, config) {
+        Ok(voxels) => println!("{}", voxels),
+        Err(err) => println!("Invalid input: {}", err),
+    }
+}
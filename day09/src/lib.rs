@@ -1,149 +1,396 @@
 use std::collections::HashSet;
+use std::fmt;
 
-pub fn process_part1(input: &str) -> usize {
-    process_help(input, 2)
+mod render;
+mod stats;
+
+pub use render::{render_rope, replay, trail_image};
+pub use stats::{rope_stats, BoundingBox, KnotStats, RopeStats};
+
+// steps and the rounds after a jump are simulated one at a time, so the
+// length of a motion bounds the work it causes
+const MAX_MOTION: usize = 10_000;
+
+pub fn process_part1(input: &str) -> Result<usize, Error> {
+    count_tail_posns(input, RopeConfig::new(2))
 }
 
-pub fn process_part2(input: &str) -> usize {
-    process_help(input, 10)
+pub fn process_part2(input: &str) -> Result<usize, Error> {
+    count_tail_posns(input, RopeConfig::new(10))
 }
 
-fn process_help(input: &str, rope_length: usize) -> usize {
-    let motions = parse_motions(input);
-    let rope = Rope::new(rope_length);
-    let mut ropes = rope.perform_motions(motions);
-    ropes.push(rope);
-    num_of_distinct_tail_posns(ropes)
+pub fn count_tail_posns(input: &str, config: RopeConfig) -> Result<usize, Error> {
+    count_tail_posns_in::<2>(input, config)
 }
 
-fn parse_motions(input: &str) -> Vec<Motion> {
-    input
-        .lines()
-        .filter_map(|line| parse_motion(line.trim()))
-        .collect()
+pub fn count_tail_voxels(input: &str, config: RopeConfig) -> Result<usize, Error> {
+    count_tail_posns_in::<3>(input, config)
 }
 
-fn parse_motion(line: &str) -> Option<Motion> {
-    let maybe_pair = line.split_once(" ");
-    let maybe_dir = maybe_pair.and_then(|pair| pair.0.chars().nth(0));
-    let maybe_count = maybe_pair.and_then(|pair| pair.1.parse::<usize>().ok());
+pub fn count_tail_posns_in<const N: usize>(
+    input: &str,
+    config: RopeConfig,
+) -> Result<usize, Error> {
+    let motions = parse_motions::<N>(input)?;
+    let mut rope = Rope::<N>::new(config);
+    let mut visited = HashSet::new();
+    rope.perform_motions(&motions, |knots| {
+        visited.insert(knots[knots.len() - 1]);
+    });
+    Ok(visited.len())
+}
 
-    match (maybe_dir, maybe_count) {
-        (Some('U'), Some(count)) => Some(Motion {
-            direction: Direction::Up,
-            count: count,
-        }),
-        (Some('D'), Some(count)) => Some(Motion {
-            direction: Direction::Down,
-            count: count,
-        }),
-        (Some('L'), Some(count)) => Some(Motion {
-            direction: Direction::Left,
-            count: count,
-        }),
-        (Some('R'), Some(count)) => Some(Motion {
-            direction: Direction::Right,
-            count: count,
-        }),
-        _ => None,
+// reach is how far, in king moves, a knot may get from the one before it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RopeConfig {
+    pub length: usize,
+    pub reach: usize,
+}
+
+impl RopeConfig {
+    pub fn new(length: usize) -> Self {
+        RopeConfig { length, reach: 1 }
+    }
+
+    pub fn with_reach(self, reach: usize) -> Self {
+        RopeConfig { reach, ..self }
     }
 }
 
-fn num_of_distinct_tail_posns(ropes: Vec<Rope>) -> usize {
-    HashSet::<_>::from_iter(ropes.iter().filter_map(|rope| rope.knots.last())).len()
+#[derive(PartialEq, Debug)]
+pub enum Error {
+    MalformedMotion {
+        index: usize,
+        line: String,
+    },
+    UnknownDirection {
+        index: usize,
+        direction: String,
+    },
+    TooManyDimensions {
+        index: usize,
+        needed: usize,
+        available: usize,
+    },
+    MotionTooLong {
+        index: usize,
+        length: usize,
+    },
+    CanvasTooLarge {
+        width: usize,
+        height: usize,
+    },
+    OutOfRange {
+        index: usize,
+    },
 }
 
-struct Rope {
-    knots: Vec<Posn>,
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MalformedMotion { index, line } => write!(
+                f,
+                "line {}: cannot parse \"{}\", expected a direction and a count or J <dx> <dy> [<dz>...]",
+                index + 1,
+                line
+            ),
+            Error::UnknownDirection { index, direction } => write!(
+                f,
+                "line {}: unknown direction \"{}\", expected U, D, L, R, UL, UR, DL, DR, F or B",
+                index + 1,
+                direction
+            ),
+            Error::TooManyDimensions {
+                index,
+                needed,
+                available,
+            } => write!(
+                f,
+                "line {}: the motion needs {} dimensions but the rope only has {}",
+                index + 1,
+                needed,
+                available
+            ),
+            Error::MotionTooLong { index, length } => write!(
+                f,
+                "line {}: the motion covers {} cells along an axis, at most {} are allowed",
+                index + 1,
+                length,
+                MAX_MOTION
+            ),
+            Error::CanvasTooLarge { width, height } => write!(
+                f,
+                "the rope covers {}x{} cells, too many to draw",
+                width, height
+            ),
+            Error::OutOfRange { index } => write!(
+                f,
+                "line {}: the motion takes the head beyond the range of a 32 bit coordinate",
+                index + 1
+            ),
+        }
+    }
 }
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
-struct Posn {
-    x: i32,
-    y: i32,
+// the head is followed to where each motion ends, so the rope never leaves
+// the coordinate range while the motions are performed
+fn parse_motions<const N: usize>(input: &str) -> Result<Vec<Motion>, Error> {
+    let mut head = Posn([0; N]);
+    input
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| {
+            let motion = parse_motion(index, line)?;
+            let needed = motion.dimensions();
+            if needed > N {
+                return Err(Error::TooManyDimensions {
+                    index,
+                    needed,
+                    available: N,
+                });
+            }
+            head = motion.end(head).ok_or(Error::OutOfRange { index })?;
+            Ok(motion)
+        })
+        .collect()
+}
+
+// `<direction> <count>`, or `J <dx> <dy> [<dz>...]` with y growing upwards
+fn parse_motion(index: usize, line: &str) -> Result<Motion, Error> {
+    let malformed = || Error::MalformedMotion {
+        index,
+        line: line.to_string(),
+    };
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    match tokens.as_slice() {
+        ["J", deltas @ ..] if deltas.len() >= 2 => {
+            let mut delta = deltas
+                .iter()
+                .map(|delta| delta.parse::<i32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| malformed())?;
+            // positions grow downwards, like the rows of a grid
+            delta[1] = delta[1].checked_neg().ok_or_else(malformed)?;
+            let length = delta
+                .iter()
+                .map(|component| component.unsigned_abs() as usize)
+                .max()
+                .unwrap_or(0);
+            if length > MAX_MOTION {
+                return Err(Error::MotionTooLong { index, length });
+            }
+            Ok(Motion::Jump(delta))
+        }
+        ["J", ..] => Err(malformed()),
+        [direction, count] => {
+            let direction = Direction::parse(direction).ok_or_else(|| Error::UnknownDirection {
+                index,
+                direction: direction.to_string(),
+            })?;
+            let count = count.parse().map_err(|_| malformed())?;
+            if count > MAX_MOTION {
+                return Err(Error::MotionTooLong {
+                    index,
+                    length: count,
+                });
+            }
+            Ok(Motion::Steps { direction, count })
+        }
+        _ => Err(malformed()),
+    }
 }
 
+struct Rope<const N: usize> {
+    knots: Vec<Posn<N>>,
+    reach: usize,
+}
+
+// x, y, z and any further axes; y grows downwards like on the puzzle's pictures
+#[derive(Copy, Clone, Hash, Eq, PartialEq)]
+struct Posn<const N: usize = 2>([i32; N]);
+
 #[derive(Copy, Clone)]
 enum Direction {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    Forward,
+    Backward,
 }
 
-#[derive(Copy, Clone)]
-struct Motion {
-    direction: Direction,
-    count: usize,
+enum Motion {
+    Steps { direction: Direction, count: usize },
+    Jump(Vec<i32>),
+}
+
+impl Motion {
+    fn dimensions(&self) -> usize {
+        let delta: &[i32] = match self {
+            Motion::Steps { direction, .. } => &direction.delta(),
+            Motion::Jump(delta) => delta,
+        };
+        delta
+            .iter()
+            .rposition(|component| *component != 0)
+            .map_or(2, |axis| (axis + 1).max(2))
+    }
+
+    fn end<const N: usize>(&self, start: Posn<N>) -> Option<Posn<N>> {
+        match self {
+            Motion::Steps { direction, count } => {
+                let count = i32::try_from(*count).ok()?;
+                let delta = direction
+                    .delta()
+                    .map(|component| component.checked_mul(count));
+                start.moved_by(&delta.into_iter().collect::<Option<Vec<_>>>()?)
+            }
+            Motion::Jump(delta) => start.moved_by(delta),
+        }
+    }
 }
 
-impl Rope {
-    fn new(length: usize) -> Self {
-        let length = if length <= 0 { 1 } else { length };
+impl<const N: usize> Rope<N> {
+    fn new(config: RopeConfig) -> Self {
+        let length = if config.length == 0 { 1 } else { config.length };
         Rope {
-            knots: vec![Posn { x: 0, y: 0 }; length],
+            knots: vec![Posn([0; N]); length],
+            reach: config.reach,
         }
     }
 
-    fn perform_motions(&self, motions: Vec<Motion>) -> Vec<Rope> {
-        let mut ropes: Vec<Rope> = Vec::new();
-        let mut rope = self;
+    // calls on_step before the first step and after every step
+    fn perform_motions<F>(&mut self, motions: &[Motion], mut on_step: F)
+    where
+        F: FnMut(&[Posn<N>]),
+    {
+        on_step(&self.knots);
         for motion in motions {
-            for _ in 0..motion.count {
-                let next_rope = rope.move_one_step(motion.direction);
-                ropes.push(next_rope);
-                rope = &ropes[ropes.len() - 1];
+            match motion {
+                Motion::Steps { direction, count } => {
+                    let delta = direction.delta();
+                    for _ in 0..*count {
+                        self.move_head(&delta, &mut on_step);
+                    }
+                }
+                Motion::Jump(delta) => self.move_head(delta, &mut on_step),
             }
         }
+    }
 
-        ropes
+    // every round after a jump is a step of its own, so trails stay continuous
+    fn move_head<F>(&mut self, delta: &[i32], on_step: &mut F)
+    where
+        F: FnMut(&[Posn<N>]),
+    {
+        self.knots[0] = self.knots[0]
+            .moved_by(delta)
+            .expect("parse_motions keeps the head within range");
+        // a knot is at most one cell out of reach after its leader moved to
+        // a neighboring cell, so a single round settles single cell moves
+        let single_cell = delta.iter().all(|component| component.abs() <= 1);
+        self.follow(single_cell);
+        on_step(&self.knots);
+        if !single_cell {
+            while self.follow(false) {
+                on_step(&self.knots);
+            }
+        }
     }
 
-    fn move_one_step(&self, direction: Direction) -> Self {
-        let mut head = self.knots[0].move_one_step(direction);
-        let mut knots = vec![head];
-        for next in self.knots[1..].iter() {
-            head = next.move_relativete_to_head(head);
-            knots.push(head);
+    // when the rope was settled, the knots behind one that stays put stay too
+    fn follow(&mut self, settled: bool) -> bool {
+        let mut moved = false;
+        for idx in 1..self.knots.len() {
+            let next = self.knots[idx].move_relativete_to_head(self.knots[idx - 1], self.reach);
+            if next != self.knots[idx] {
+                self.knots[idx] = next;
+                moved = true;
+            } else if settled {
+                break;
+            }
         }
-        Rope { knots }
+        moved
     }
 }
 
-impl Posn {
-    fn move_one_step(self, direction: Direction) -> Self {
-        match direction {
-            Direction::Up => Self {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Direction::Down => Self {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Direction::Left => Self {
-                x: self.x - 1,
-                y: self.y,
-            },
-            Direction::Right => Self {
-                x: self.x + 1,
-                y: self.y,
-            },
+impl Direction {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "U" => Some(Direction::Up),
+            "D" => Some(Direction::Down),
+            "L" => Some(Direction::Left),
+            "R" => Some(Direction::Right),
+            "UL" => Some(Direction::UpLeft),
+            "UR" => Some(Direction::UpRight),
+            "DL" => Some(Direction::DownLeft),
+            "DR" => Some(Direction::DownRight),
+            "F" => Some(Direction::Forward),
+            "B" => Some(Direction::Backward),
+            _ => None,
         }
     }
 
-    fn move_relativete_to_head(self, head: Posn) -> Posn {
-        let x_diff = head.x - self.x;
-        let y_diff = head.y - self.y;
-        if x_diff.abs() > 1 || y_diff.abs() > 1 {
-            Posn {
-                x: self.x + x_diff.signum(),
-                y: self.y + y_diff.signum(),
-            }
+    fn delta(self) -> [i32; 3] {
+        match self {
+            Direction::Up => [0, -1, 0],
+            Direction::Down => [0, 1, 0],
+            Direction::Left => [-1, 0, 0],
+            Direction::Right => [1, 0, 0],
+            Direction::UpLeft => [-1, -1, 0],
+            Direction::UpRight => [1, -1, 0],
+            Direction::DownLeft => [-1, 1, 0],
+            Direction::DownRight => [1, 1, 0],
+            Direction::Forward => [0, 0, 1],
+            Direction::Backward => [0, 0, -1],
+        }
+    }
+}
+
+impl<const N: usize> Posn<N> {
+    // the motions have been checked not to use any further axis
+    fn moved_by(self, delta: &[i32]) -> Option<Self> {
+        let mut coords = self.0;
+        for (coord, component) in coords.iter_mut().zip(delta) {
+            *coord = coord.checked_add(*component)?;
+        }
+        Some(Posn(coords))
+    }
+
+    fn move_relativete_to_head(self, head: Posn<N>, reach: usize) -> Posn<N> {
+        if self.chebyshev_distance(head) > reach {
+            Posn(std::array::from_fn(|axis| {
+                self.0[axis] + (head.0[axis] - self.0[axis]).signum()
+            }))
         } else {
             self
         }
     }
+
+    fn chebyshev_distance(self, other: Posn<N>) -> usize {
+        self.0
+            .iter()
+            .zip(other.0)
+            .map(|(a, b)| a.abs_diff(b) as usize)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Posn {
+    fn x(self) -> i32 {
+        self.0[0]
+    }
+
+    fn y(self) -> i32 {
+        self.0[1]
+    }
 }
 
 #[cfg(test)]
@@ -162,47 +409,246 @@ mod tests {
             L 5
             R 2";
         let result = process_part1(input);
-        assert_eq!(13, result);
+        assert_eq!(Ok(13), result);
     }
 
     #[test]
     fn test_move_one_step() {
-        let rope = Rope::new(10);
-        let ropes = rope.perform_motions(vec![
-            Motion {
+        let mut rope = Rope::<2>::new(RopeConfig::new(10));
+        let motions = [
+            Motion::Steps {
                 direction: Direction::Right,
                 count: 4,
             },
-            Motion {
+            Motion::Steps {
                 direction: Direction::Up,
                 count: 4,
             },
-        ]);
+        ];
+        rope.perform_motions(&motions, |_| {});
+
+        let head = &rope.knots[0];
+        assert_eq!(4, head.x());
+        assert_eq!(-4, head.y());
+
+        let knot = &rope.knots[1];
+        assert_eq!(4, knot.x());
+        assert_eq!(-3, knot.y());
+
+        let knot = &rope.knots[2];
+        assert_eq!(4, knot.x());
+        assert_eq!(-2, knot.y());
+
+        let knot = &rope.knots[3];
+        assert_eq!(3, knot.x());
+        assert_eq!(-2, knot.y());
+
+        let knot = &rope.knots[4];
+        assert_eq!(2, knot.x());
+        assert_eq!(-2, knot.y());
+
+        let knot = &rope.knots[5];
+        assert_eq!(1, knot.x());
+        assert_eq!(-1, knot.y());
+    }
+
+    #[test]
+    fn test_process_part2() {
+        let input = "
+            R 5
+            U 8
+            L 8
+            D 3
+            R 17
+            D 10
+            L 25
+            U 20";
+        assert_eq!(Ok(36), process_part2(input));
+    }
 
-        let last_rope = ropes.last().unwrap();
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(Error::UnknownDirection {
+                index: 1,
+                direction: String::from("X")
+            }),
+            process_part1("R 4\nX 2")
+        );
+        assert_eq!(
+            Err(Error::MalformedMotion {
+                index: 0,
+                line: String::from("R four")
+            }),
+            process_part1("R four")
+        );
+        assert!(process_part1("R").is_err());
+        assert!(process_part1("J 1").is_err());
+        assert!(process_part1("J 1 x").is_err());
+    }
+
+    #[test]
+    fn test_diagonal_motions() {
+        let rope = RopeConfig::new(2);
+        assert_eq!(Ok(3), count_tail_posns("UR 3", rope));
+        assert_eq!(Ok(4), count_tail_posns("DL 2\nDR 2", rope));
+    }
+
+    #[test]
+    fn test_jumps() {
+        assert_eq!(Ok(5), count_tail_posns("J 5 0", RopeConfig::new(2)));
+        assert_eq!(Ok(4), count_tail_posns("J 5 0", RopeConfig::new(3)));
+
+        let mut rope = Rope::<2>::new(RopeConfig::new(2));
+        rope.perform_motions(&[Motion::Jump(vec![0, -3])], |_| {});
+        assert_eq!(-3, rope.knots[0].y());
+        assert_eq!(-2, rope.knots[1].y());
+    }
+
+    #[test]
+    fn test_reach() {
+        assert_eq!(
+            Ok(9),
+            count_tail_posns("R 10", RopeConfig::new(2).with_reach(2))
+        );
+        assert_eq!(
+            Ok(11),
+            count_tail_posns("R 10", RopeConfig::new(2).with_reach(0))
+        );
+    }
 
-        let head = &last_rope.knots[0];
-        assert_eq!(4, head.x);
-        assert_eq!(-4, head.y);
+    #[test]
+    fn test_three_dimensions() {
+        let rope = RopeConfig::new(2);
+        assert_eq!(Ok(3), count_tail_voxels("F 3", rope));
+        assert_eq!(Ok(3), count_tail_voxels("R 2\nF 2", rope));
+        assert_eq!(Ok(3), count_tail_voxels("J 0 0 3", rope));
+        assert_eq!(Ok(3), count_tail_voxels("F 2\nB 4", rope));
+
+        // planar motions behave the same in any number of dimensions
+        let input = "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20";
+        assert_eq!(
+            count_tail_posns(input, RopeConfig::new(10)),
+            count_tail_voxels(input, RopeConfig::new(10))
+        );
+        assert_eq!(Ok(36), count_tail_posns_in::<4>(input, RopeConfig::new(10)));
+    }
+
+    #[test]
+    fn test_too_many_dimensions() {
+        assert_eq!(
+            Err(Error::TooManyDimensions {
+                index: 1,
+                needed: 3,
+                available: 2
+            }),
+            process_part1("R 1\nF 1")
+        );
+        assert_eq!(
+            Err(Error::TooManyDimensions {
+                index: 0,
+                needed: 4,
+                available: 3
+            }),
+            count_tail_voxels("J 0 0 0 1", RopeConfig::new(2))
+        );
+        assert_eq!(Ok(1), process_part1("J 1 1 0"));
+    }
 
-        let knot = &last_rope.knots[1];
-        assert_eq!(4, knot.x);
-        assert_eq!(-3, knot.y);
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(
+            Err(Error::MalformedMotion {
+                index: 0,
+                line: String::from("J 0 -2147483648")
+            }),
+            process_part1("J 0 -2147483648")
+        );
+        // 214748 motions of the longest length take the head to 2147480000
+        let input = "R 10000\n".repeat(214_748);
+        assert_eq!(
+            Err(Error::OutOfRange { index: 214_749 }),
+            process_part1(&(input.clone() + "J 3647 0\nJ 1 0"))
+        );
+        assert_eq!(
+            Err(Error::OutOfRange { index: 214_748 }),
+            count_tail_voxels(&(input.replace('R', "B") + "B 3649"), RopeConfig::new(2))
+        );
+    }
 
-        let knot = &last_rope.knots[2];
-        assert_eq!(4, knot.x);
-        assert_eq!(-2, knot.y);
+    #[test]
+    fn test_motion_too_long() {
+        assert_eq!(Ok(10_000), process_part1("J 10000 0"));
+        assert_eq!(Ok(10_000), process_part1("L 10000"));
+        assert_eq!(
+            Err(Error::MotionTooLong {
+                index: 1,
+                length: 2147483647
+            }),
+            process_part1("R 1\nJ 2147483647 0")
+        );
+        assert_eq!(
+            Err(Error::MotionTooLong {
+                index: 0,
+                length: 2_000_000_000
+            }),
+            process_part1("R 2000000000")
+        );
+        assert_eq!(
+            Err(Error::MotionTooLong {
+                index: 0,
+                length: 10_001
+            }),
+            count_tail_voxels("J 0 0 -10001", RopeConfig::new(2))
+        );
+    }
 
-        let knot = &last_rope.knots[3];
-        assert_eq!(3, knot.x);
-        assert_eq!(-2, knot.y);
+    #[test]
+    #[ignore]
+    fn bench_many_motions() {
+        let motions = random_motions(2_000_000, 3);
+        let steps: usize = motions
+            .iter()
+            .map(|motion| match motion {
+                Motion::Steps { count, .. } => *count,
+                Motion::Jump(_) => 1,
+            })
+            .sum();
 
-        let knot = &last_rope.knots[4];
-        assert_eq!(2, knot.x);
-        assert_eq!(-2, knot.y);
+        let start = std::time::Instant::now();
+        let mut visited = HashSet::new();
+        Rope::<2>::new(RopeConfig::new(10)).perform_motions(&motions, |knots| {
+            visited.insert(knots[knots.len() - 1]);
+        });
+        let visited = visited.len();
+        println!(
+            "{} motions, {} steps: {:?} ({} tail positions)",
+            motions.len(),
+            steps,
+            start.elapsed(),
+            visited
+        );
+    }
 
-        let knot = &last_rope.knots[5];
-        assert_eq!(1, knot.x);
-        assert_eq!(-1, knot.y);
+    fn random_motions(num_of_motions: usize, seed: u64) -> Vec<Motion> {
+        let directions = [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ];
+        let mut state = seed;
+        (0..num_of_motions)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let bits = (state >> 33) as usize;
+                Motion::Steps {
+                    direction: directions[bits % 4],
+                    count: 1 + (bits >> 2) % 20,
+                }
+            })
+            .collect()
     }
 }
@@ -0,0 +1,162 @@
+use crate::stats::motion_stats;
+use crate::{parse_motions, BoundingBox, Error, Motion, Posn, Rope, RopeConfig};
+use grid::{Coord, Grid};
+
+const START: Posn = Posn([0, 0]);
+const MAX_CELLS: usize = 1 << 24;
+
+// just large enough for every knot over the whole simulation
+struct Canvas {
+    bounds: BoundingBox,
+}
+
+impl Canvas {
+    fn new(motions: &[Motion], config: RopeConfig) -> Result<Self, Error> {
+        let stats = motion_stats(motions, config);
+        let bounds = stats
+            .knots
+            .iter()
+            .map(|knot| knot.bounds)
+            .reduce(BoundingBox::union)
+            .expect("a rope has at least one knot");
+        let (width, height) = (bounds.width(), bounds.height());
+        match width.checked_mul(height) {
+            Some(cells) if cells <= MAX_CELLS => Ok(Canvas { bounds }),
+            _ => Err(Error::CanvasTooLarge { width, height }),
+        }
+    }
+
+    fn blank<T: Clone>(&self, value: T) -> Grid<T> {
+        Grid::new(self.bounds.width(), self.bounds.height(), value)
+    }
+
+    fn coord(&self, posn: Posn) -> Coord {
+        Coord::new(
+            self.bounds.min_y.abs_diff(posn.y()) as usize,
+            self.bounds.min_x.abs_diff(posn.x()) as usize,
+        )
+    }
+
+    // knots nearer the head cover the ones behind them
+    fn draw(&self, knots: &[Posn], trail: &Grid<bool>) -> String {
+        let mut cells = trail.map(|visited| if *visited { '#' } else { '.' });
+        cells[self.coord(START)] = 's';
+        for (idx, knot) in knots.iter().enumerate().rev() {
+            cells[self.coord(*knot)] = knot_label(idx, knots.len());
+        }
+        cells.render(|_, cell| *cell)
+    }
+}
+
+fn knot_label(idx: usize, rope_length: usize) -> char {
+    if idx == 0 {
+        'H'
+    } else if idx + 1 == rope_length {
+        'T'
+    } else {
+        char::from_digit(idx as u32, 10).unwrap_or('*')
+    }
+}
+
+pub fn render_rope(input: &str, config: RopeConfig) -> Result<String, Error> {
+    let motions = parse_motions::<2>(input)?;
+    let canvas = Canvas::new(&motions, config)?;
+    let mut trail = canvas.blank(false);
+    let mut rope = Rope::<2>::new(config);
+    rope.perform_motions(&motions, |knots| {
+        trail[canvas.coord(knots[knots.len() - 1])] = true;
+    });
+    Ok(canvas.draw(&rope.knots, &trail))
+}
+
+pub fn replay<F>(input: &str, config: RopeConfig, mut on_frame: F) -> Result<(), Error>
+where
+    F: FnMut(&str),
+{
+    let motions = parse_motions::<2>(input)?;
+    let canvas = Canvas::new(&motions, config)?;
+    let mut trail = canvas.blank(false);
+    Rope::<2>::new(config).perform_motions(&motions, |knots| {
+        trail[canvas.coord(knots[knots.len() - 1])] = true;
+        on_frame(&canvas.draw(knots, &trail));
+    });
+    Ok(())
+}
+
+pub fn trail_image(input: &str, config: RopeConfig, scale: usize) -> Result<Vec<u8>, Error> {
+    let motions = parse_motions::<2>(input)?;
+    let canvas = Canvas::new(&motions, config)?;
+    let mut trail = canvas.blank(false);
+    Rope::<2>::new(config).perform_motions(&motions, |knots| {
+        trail[canvas.coord(knots[knots.len() - 1])] = true;
+    });
+    let start = canvas.coord(START);
+    Ok(trail.to_ppm(scale, |coord, visited| {
+        if coord == start {
+            [220, 40, 40]
+        } else if *visited {
+            [240, 240, 240]
+        } else {
+            [20, 20, 30]
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2";
+
+    #[test]
+    fn test_render_rope() {
+        let expected = ["..##..", "...##.", ".TH##.", "....#.", "s###.."].join("\n");
+        assert_eq!(Ok(expected), render_rope(EXAMPLE, RopeConfig::new(2)));
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut frames = Vec::new();
+        replay("R 4\nU 2", RopeConfig::new(3), |frame| {
+            frames.push(frame.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(7, frames.len());
+        assert_eq!([".....", ".....", "H...."].join("\n"), frames[0]);
+        assert_eq!([".....", ".....", "s#T1H"].join("\n"), frames[4]);
+        assert_eq!(["....H", "...T1", "s##.."].join("\n"), frames[6]);
+    }
+
+    #[test]
+    fn test_canvas_too_large() {
+        let input = "J 10000 0\nJ 0 10000";
+        assert_eq!(
+            Err(Error::CanvasTooLarge {
+                width: 10_001,
+                height: 10_001
+            }),
+            render_rope(input, RopeConfig::new(2))
+        );
+    }
+
+    #[test]
+    fn test_knot_label() {
+        assert_eq!('H', knot_label(0, 10));
+        assert_eq!('4', knot_label(4, 10));
+        assert_eq!('T', knot_label(9, 10));
+        assert_eq!('*', knot_label(12, 20));
+        assert_eq!('H', knot_label(0, 1));
+    }
+
+    #[test]
+    fn test_trail_image() {
+        let ppm = trail_image(EXAMPLE, RopeConfig::new(2), 1).unwrap();
+        let header = b"P6\n6 5\n255\n";
+        assert_eq!(header, &ppm[..header.len()]);
+        assert_eq!(header.len() + 6 * 5 * 3, ppm.len());
+        // the start is in the bottom left corner
+        let start = header.len() + 4 * 6 * 3;
+        assert_eq!([220, 40, 40], ppm[start..start + 3]);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{parse_motions, Error, Motion, Posn, Rope, RopeConfig};
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BoundingBox {
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_y: i32,
+    pub max_y: i32,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct KnotStats {
+    pub distinct_posns: usize,
+    pub bounds: BoundingBox,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct RopeStats {
+    // one entry per knot, starting with the head
+    pub knots: Vec<KnotStats>,
+    pub max_head_tail_distance: usize,
+}
+
+impl BoundingBox {
+    fn new(posn: Posn) -> Self {
+        BoundingBox {
+            min_x: posn.x(),
+            max_x: posn.x(),
+            min_y: posn.y(),
+            max_y: posn.y(),
+        }
+    }
+
+    fn include(&mut self, posn: Posn) {
+        self.min_x = self.min_x.min(posn.x());
+        self.max_x = self.max_x.max(posn.x());
+        self.min_y = self.min_y.min(posn.y());
+        self.max_y = self.max_y.max(posn.y());
+    }
+
+    pub fn width(&self) -> usize {
+        self.min_x.abs_diff(self.max_x) as usize + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.min_y.abs_diff(self.max_y) as usize + 1
+    }
+
+    pub(crate) fn union(self, other: BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min_x: self.min_x.min(other.min_x),
+            max_x: self.max_x.max(other.max_x),
+            min_y: self.min_y.min(other.min_y),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+}
+
+pub fn rope_stats(input: &str, config: RopeConfig) -> Result<RopeStats, Error> {
+    Ok(motion_stats(&parse_motions::<2>(input)?, config))
+}
+
+pub(crate) fn motion_stats(motions: &[Motion], config: RopeConfig) -> RopeStats {
+    let mut rope = Rope::<2>::new(config);
+    let mut visited: Vec<HashSet<Posn>> = vec![HashSet::new(); rope.knots.len()];
+    let mut bounds: Vec<BoundingBox> = rope.knots.iter().copied().map(BoundingBox::new).collect();
+    let mut max_head_tail_distance = 0;
+
+    rope.perform_motions(motions, |knots| {
+        for (idx, knot) in knots.iter().enumerate() {
+            visited[idx].insert(*knot);
+            bounds[idx].include(*knot);
+        }
+        let distance = knots[0].chebyshev_distance(knots[knots.len() - 1]);
+        max_head_tail_distance = max_head_tail_distance.max(distance);
+    });
+
+    RopeStats {
+        knots: visited
+            .iter()
+            .zip(bounds)
+            .map(|(posns, bounds)| KnotStats {
+                distinct_posns: posns.len(),
+                bounds,
+            })
+            .collect(),
+        max_head_tail_distance,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2";
+
+    #[test]
+    fn test_rope_stats() {
+        let stats = rope_stats(EXAMPLE, RopeConfig::new(2)).unwrap();
+        assert_eq!(2, stats.knots.len());
+        assert_eq!(13, stats.knots[1].distinct_posns);
+        assert_eq!(
+            BoundingBox {
+                min_x: 0,
+                max_x: 5,
+                min_y: -4,
+                max_y: 0
+            },
+            stats.knots[0].bounds
+        );
+        assert_eq!(6, stats.knots[0].bounds.width());
+        assert_eq!(5, stats.knots[0].bounds.height());
+        assert_eq!(1, stats.max_head_tail_distance);
+    }
+
+    #[test]
+    fn test_rope_stats_long_rope() {
+        let input = "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20";
+        let stats = rope_stats(input, RopeConfig::new(10)).unwrap();
+        assert_eq!(10, stats.knots.len());
+        assert_eq!(36, stats.knots[9].distinct_posns);
+        assert_eq!(96, stats.knots[0].distinct_posns);
+        assert_eq!(9, stats.max_head_tail_distance);
+    }
+}
@@ -1,3 +1,7 @@
+use grid::{Coord, Grid};
+
+const CRT_WIDTH: usize = 40;
+
 pub fn process_part1(input: &str) -> isize {
     let instructions = parse_input(&input);
     let state = exec_all(&instructions);
@@ -7,8 +11,8 @@ pub fn process_part1(input: &str) -> isize {
 pub fn process_part2(input: &str) -> String {
     let instructions = parse_input(&input);
     let state = exec_all(&instructions);
-    let pixels = lit_pixels(&state.hist[0..state.hist.len() - 1]);
-    draw_pixels(&pixels)
+    let cycles = &state.hist[0..state.hist.len() - 1];
+    draw_pixels(&lit_pixels(cycles), cycles.len())
 }
 
 enum Instr {
@@ -77,29 +81,21 @@ fn sum_signal_strength(start: usize, end: usize, step: usize, hist: &[isize]) ->
         .sum()
 }
 
-fn lit_pixels(hist: &[isize]) -> Vec<bool> {
-    hist.iter()
-        .enumerate()
-        .map(|pair| {
-            let crt = (pair.0 % 40) as isize;
-            let sprite = pair.1;
-            crt >= sprite - 1 && crt <= sprite + 1
-        })
-        .collect()
-}
-
-fn draw_pixels(pixels: &[bool]) -> String {
+fn lit_pixels(hist: &[isize]) -> Grid<bool> {
+    let mut pixels = Grid::new(CRT_WIDTH, hist.len().div_ceil(CRT_WIDTH), false);
+    for (cycle, sprite) in hist.iter().enumerate() {
+        let coord = Coord::new(cycle / CRT_WIDTH, cycle % CRT_WIDTH);
+        let crt = coord.column as isize;
+        pixels[coord] = crt >= sprite - 1 && crt <= sprite + 1;
+    }
     pixels
-        .chunks(40)
-        .map(|row| draw_row(row))
-        .collect::<Vec<String>>()
-        .join("\n")
 }
 
-fn draw_row(row: &[bool]) -> String {
-    row.iter()
-        .map(|pixel| if *pixel { '#' } else { '.' })
-        .collect()
+fn draw_pixels(pixels: &Grid<bool>, count: usize) -> String {
+    let mut drawing = pixels.render(|_, pixel| if *pixel { '#' } else { '.' });
+    // the last row is only as long as the cycles that reached it
+    drawing.truncate(drawing.len() - (pixels.width() * pixels.height() - count));
+    drawing
 }
 
 #[cfg(test)]
@@ -113,6 +109,13 @@ mod tests {
         assert_eq!([1, 1, 1, 4, 4, -1], hist.as_slice());
     }
 
+    #[test]
+    fn test_draw_partial_row() {
+        let hist = [1; 42];
+        let drawing = draw_pixels(&lit_pixels(&hist), hist.len());
+        assert_eq!(format!("###{}\n##", ".".repeat(37)), drawing);
+    }
+
     #[test]
     fn test_process_part1() {
         let input = "addx 15
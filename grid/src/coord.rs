@@ -0,0 +1,63 @@
+// ordered by row first, like the cells are stored
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Coord {
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Coord {
+    pub const fn new(row: usize, column: usize) -> Self {
+        Coord { row, column }
+    }
+
+    pub fn offset(self, offset: Offset) -> Option<Coord> {
+        Some(Coord {
+            row: self.row.checked_add_signed(offset.rows)?,
+            column: self.column.checked_add_signed(offset.columns)?,
+        })
+    }
+}
+
+// rows grow downwards, columns to the right
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Offset {
+    pub rows: isize,
+    pub columns: isize,
+}
+
+impl Offset {
+    pub const UP: Offset = Offset::new(-1, 0);
+    pub const DOWN: Offset = Offset::new(1, 0);
+    pub const LEFT: Offset = Offset::new(0, -1);
+    pub const RIGHT: Offset = Offset::new(0, 1);
+    pub const UP_LEFT: Offset = Offset::new(-1, -1);
+    pub const UP_RIGHT: Offset = Offset::new(-1, 1);
+    pub const DOWN_LEFT: Offset = Offset::new(1, -1);
+    pub const DOWN_RIGHT: Offset = Offset::new(1, 1);
+
+    // both neighborhoods go clockwise from up
+    pub const CARDINAL: [Offset; 4] = [Offset::UP, Offset::RIGHT, Offset::DOWN, Offset::LEFT];
+
+    pub const ALL: [Offset; 8] = [
+        Offset::UP,
+        Offset::UP_RIGHT,
+        Offset::RIGHT,
+        Offset::DOWN_RIGHT,
+        Offset::DOWN,
+        Offset::DOWN_LEFT,
+        Offset::LEFT,
+        Offset::UP_LEFT,
+    ];
+
+    pub const fn new(rows: isize, columns: isize) -> Self {
+        Offset { rows, columns }
+    }
+
+    pub fn opposite(self) -> Offset {
+        Offset::new(-self.rows, -self.columns)
+    }
+
+    pub fn scaled(self, factor: isize) -> Offset {
+        Offset::new(self.rows * factor, self.columns * factor)
+    }
+}
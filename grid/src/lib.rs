@@ -0,0 +1,377 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+mod coord;
+
+pub use coord::{Coord, Offset};
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum GridError {
+    Empty,
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidCharacter {
+        row: usize,
+        column: usize,
+        found: char,
+    },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "the grid has no cells"),
+            GridError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} cells but the first row has {}",
+                row + 1,
+                found,
+                expected
+            ),
+            GridError::InvalidCharacter { row, column, found } => write!(
+                f,
+                "row {}, column {}: unexpected character {:?}",
+                row + 1,
+                column + 1,
+                found
+            ),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        let height = cells.len().checked_div(width).unwrap_or(0);
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "{} cells do not fill rows of {}",
+            cells.len(),
+            width
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let width = match rows.first() {
+            Some(first) if !first.is_empty() => first.len(),
+            _ => return Err(GridError::Empty),
+        };
+        if let Some((row, found)) = rows
+            .iter()
+            .map(Vec::len)
+            .enumerate()
+            .find(|(_, len)| *len != width)
+        {
+            return Err(GridError::RaggedRow {
+                row,
+                expected: width,
+                found,
+            });
+        }
+        Ok(Grid::from_vec(width, rows.into_iter().flatten().collect()))
+    }
+
+    pub fn parse<F>(input: &str, mut parse_cell: F) -> Result<Self, GridError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(column, c)| {
+                        parse_cell(c).ok_or(GridError::InvalidCharacter {
+                            row,
+                            column,
+                            found: c,
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Grid::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.row < self.height && coord.column < self.width
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        if self.contains(coord) {
+            Some(&self.cells[coord.row * self.width + coord.column])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        if self.contains(coord) {
+            Some(&mut self.cells[coord.row * self.width + coord.column])
+        } else {
+            None
+        }
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| Coord::new(row, column)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, column: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(column < self.width, "column {} out of bounds", column);
+        self.cells[column..].iter().step_by(self.width)
+    }
+
+    pub fn neighbors<'a>(
+        &self,
+        coord: Coord,
+        offsets: &'a [Offset],
+    ) -> impl Iterator<Item = Coord> + 'a {
+        let (width, height) = (self.width, self.height);
+        offsets
+            .iter()
+            .filter_map(move |offset| coord.offset(*offset))
+            .filter(move |neighbor| neighbor.row < height && neighbor.column < width)
+    }
+
+    pub fn neighbors4(&self, coord: Coord) -> impl Iterator<Item = Coord> {
+        self.neighbors(coord, &Offset::CARDINAL)
+    }
+
+    pub fn neighbors8(&self, coord: Coord) -> impl Iterator<Item = Coord> {
+        self.neighbors(coord, &Offset::ALL)
+    }
+
+    // nearest first, without the origin itself
+    pub fn ray(&self, origin: Coord, step: Offset) -> impl Iterator<Item = Coord> {
+        assert!(step != Offset::new(0, 0), "a ray needs a non-zero step");
+        let (width, height) = (self.width, self.height);
+        let next = move |coord: &Coord| {
+            coord
+                .offset(step)
+                .filter(|next| next.row < height && next.column < width)
+        };
+        std::iter::successors(next(&origin), next)
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn render<D, F>(&self, mut render_cell: F) -> String
+    where
+        D: fmt::Display,
+        F: FnMut(Coord, &T) -> D,
+    {
+        (0..self.height)
+            .map(|row| {
+                self.row(row)
+                    .iter()
+                    .enumerate()
+                    .map(|(column, cell)| render_cell(Coord::new(row, column), cell).to_string())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // binary (P6) pixmap with a `scale` × `scale` square per cell
+    pub fn to_ppm<F>(&self, scale: usize, mut color: F) -> Vec<u8>
+    where
+        F: FnMut(Coord, &T) -> [u8; 3],
+    {
+        let (width, height) = (self.width * scale, self.height * scale);
+        let mut bytes = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for row in 0..self.height {
+            let pixels: Vec<u8> = self
+                .row(row)
+                .iter()
+                .enumerate()
+                .flat_map(|(column, cell)| color(Coord::new(row, column), cell).repeat(scale))
+                .collect();
+            for _ in 0..scale {
+                bytes.extend(&pixels);
+            }
+        }
+        bytes
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        self.get(coord)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", coord))
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        self.get_mut(coord)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", coord))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, GridError> {
+        Grid::parse(input, |c| c.to_digit(10))
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!(6, grid[Coord::new(1, 2)]);
+        assert_eq!(None, grid.get(Coord::new(2, 0)));
+
+        assert_eq!(Err(GridError::Empty), digits(""));
+        assert_eq!(
+            Err(GridError::RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 2
+            }),
+            digits("123\n45\n")
+        );
+        assert_eq!(
+            Err(GridError::InvalidCharacter {
+                row: 1,
+                column: 0,
+                found: '#'
+            }),
+            digits("123\n#56\n")
+        );
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!(
+            vec![&[1, 2, 3][..], &[4, 5, 6][..]],
+            grid.rows().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![&2, &5], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(vec![&6, &3], grid.column(2).rev().collect::<Vec<_>>());
+        assert_eq!(
+            vec![Coord::new(0, 0), Coord::new(0, 1), Coord::new(0, 2)],
+            grid.coords().take(3).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(
+            vec![Coord::new(0, 1), Coord::new(1, 0)],
+            grid.neighbors4(Coord::new(0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(4, grid.neighbors4(Coord::new(1, 1)).count());
+        assert_eq!(8, grid.neighbors8(Coord::new(1, 1)).count());
+        assert_eq!(3, grid.neighbors8(Coord::new(2, 2)).count());
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = Grid::new(4, 3, ());
+        assert_eq!(
+            vec![Coord::new(1, 2), Coord::new(1, 3)],
+            grid.ray(Coord::new(1, 1), Offset::RIGHT)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Coord::new(1, 2), Coord::new(0, 3)],
+            grid.ray(Coord::new(2, 1), Offset::UP_RIGHT)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, grid.ray(Coord::new(0, 0), Offset::LEFT).count());
+        assert_eq!(
+            vec![Coord::new(0, 2)],
+            grid.ray(Coord::new(0, 0), Offset::RIGHT.scaled(2))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let grid = Grid::from_vec(2, vec![true, false, false, true]);
+        assert_eq!("#.\n.#", grid.render(|_, lit| if *lit { '#' } else { '.' }));
+        assert_eq!(
+            "0,0 0,1 \n1,0 1,1 ",
+            grid.render(|coord, _| format!("{},{} ", coord.row, coord.column))
+        );
+    }
+
+    #[test]
+    fn test_to_ppm() {
+        let grid = Grid::from_vec(2, vec![true, false]);
+        let ppm = grid.to_ppm(2, |_, lit| if *lit { [255, 0, 0] } else { [0, 0, 9] });
+        let header = b"P6\n4 2\n255\n";
+
+        assert_eq!(header, &ppm[..header.len()]);
+        let row = [255, 0, 0, 255, 0, 0, 0, 0, 9, 0, 0, 9];
+        assert_eq!([row, row].concat(), ppm[header.len()..]);
+    }
+}